@@ -0,0 +1,14 @@
+use platform::Registry;
+
+fn registry() -> Registry {
+    Registry::new()
+        .register(day01::Day01)
+        .register(day02::Day02)
+        .register(day03::Day03)
+        .register(day04::Day04)
+        .register(day05::Day05)
+}
+
+fn main() -> std::process::ExitCode {
+    platform::runner::run(registry())
+}
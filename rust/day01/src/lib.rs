@@ -0,0 +1,59 @@
+use platform::*;
+
+#[derive(Debug, Clone)]
+pub struct Day01;
+
+impl Challenge for Day01 {
+    const DAY: u8 = 1;
+    type Input = Vec<u16>;
+
+    fn parse(&self, data: &str) -> anyhow::Result<Self::Input> {
+        let numbers = parse::numbers(data)?;
+        log::debug!("Loaded {} numbers", numbers.len());
+        Ok(numbers)
+    }
+
+    fn stage1(&self, input: &Self::Input) -> anyhow::Result<Answer> {
+        let num_increasing = input
+            .windows(2)
+            .filter(|arr| {
+                let last = arr[0];
+                let current = arr[1];
+                current > last
+            })
+            .count();
+        Ok(num_increasing.into())
+    }
+
+    fn stage2(&self, input: &Self::Input) -> anyhow::Result<Answer> {
+        let windows_sum = Vec::from_iter(input.windows(3).map(|l| l.iter().sum::<u16>()));
+        let num_increasing = windows_sum
+            .windows(2)
+            .filter(|arr| {
+                let last = arr[0];
+                let current = arr[1];
+                current > last
+            })
+            .count();
+        Ok(num_increasing.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use platform::Challenge;
+
+    use crate::Day01;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(vec![199, 200, 208], Day01.parse("199\n200\n208\n").unwrap());
+        // Invalid depths are reported instead of being skipped
+        let err = Day01.parse("199\n2oo\n208\n").unwrap_err();
+        assert_eq!(
+            "line 2, column 1: Invalid value \"2oo\": invalid digit found in string",
+            err.to_string()
+        );
+        assert!(Day01.parse("199\n-1\n").is_err());
+    }
+}
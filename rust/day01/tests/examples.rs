@@ -0,0 +1 @@
+platform::examples!(day01::Day01);
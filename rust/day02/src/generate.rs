@@ -0,0 +1,58 @@
+//! Random command lists, checked against a naive reference.
+
+use platform::{
+    generate::{Generate, Rng},
+    rand::Rng as _,
+    Answer, Stage,
+};
+
+use crate::Day02;
+
+impl Generate for Day02 {
+    /// Commands never bring the submarine above the surface, as in the puzzle inputs
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        let mut depth = 0;
+        let mut data = String::new();
+        for _ in 0..size {
+            let amount = rng.gen_range(1..10);
+            let command = match rng.gen_range(0..3) {
+                0 => "forward",
+                1 if amount <= depth => {
+                    depth -= amount;
+                    "up"
+                }
+                _ => {
+                    depth += amount;
+                    "down"
+                }
+            };
+            data += &format!("{} {}\n", command, amount);
+        }
+        data
+    }
+
+    fn reference(&self, data: &str, stage: Stage) -> Option<Answer> {
+        // The depth of the first stage is the aim of the second one
+        let (mut pos, mut depth, mut aim) = (0i64, 0i64, 0i64);
+        for line in data.lines() {
+            let (command, amount) = line.split_once(' ')?;
+            let amount: i64 = amount.parse().ok()?;
+            match command {
+                "forward" => {
+                    pos += amount;
+                    depth += aim * amount;
+                }
+                "down" => aim += amount,
+                "up" => aim -= amount,
+                _ => return None,
+            }
+        }
+        match stage {
+            Stage::Stage1 => Some((pos * aim).into()),
+            Stage::Stage2 => Some((pos * depth).into()),
+            Stage::Custom(_) => None,
+        }
+    }
+}
+
+platform::generated!(Day02);
@@ -0,0 +1,139 @@
+use std::{fmt::Debug, str::FromStr};
+
+use platform::{
+    anyhow,
+    parse::{ParseError, Pattern, Text},
+    Answer, Challenge,
+};
+use sub::Sub;
+
+mod generate;
+mod sub;
+
+#[derive(Debug, Clone)]
+pub struct Day02;
+
+#[derive(Debug, Clone, Copy)]
+pub enum Direction {
+    Forward,
+    Up,
+    Down,
+}
+
+impl FromStr for Direction {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        use Direction::*;
+        let res = match s.trim() {
+            "forward" => Forward,
+            "up" => Up,
+            "down" => Down,
+            s => anyhow::bail!("Unknown command {:?}", s),
+        };
+        Ok(res)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Command(Direction, u16);
+
+/// Layout of a command, its direction followed by its amount
+const COMMAND: Pattern = Pattern::new("{} {}");
+
+impl FromStr for Command {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(Text::new(s))
+    }
+}
+
+impl Command {
+    fn parse(text: Text) -> Result<Self, ParseError> {
+        let fields = COMMAND.fields(text)?;
+        let cmd = Self(fields[0].parse()?, fields[1].parse()?);
+        log::info!("Parsed command: {:?}", cmd);
+        Ok(cmd)
+    }
+}
+
+impl Challenge for Day02 {
+    const DAY: u8 = 2;
+    type Input = Vec<Command>;
+
+    fn parse(&self, data: &str) -> anyhow::Result<Self::Input> {
+        parse(data)
+    }
+
+    fn stage1(&self, input: &Self::Input) -> platform::anyhow::Result<Answer> {
+        Ok(process::<sub::Submarine>(input).into())
+    }
+    fn stage2(&self, input: &Self::Input) -> platform::anyhow::Result<Answer> {
+        Ok(process::<sub::SubAim>(input).into())
+    }
+}
+
+fn parse(data: &str) -> anyhow::Result<Vec<Command>> {
+    Ok(Text::new(data)
+        .lines()
+        .map(Command::parse)
+        .collect::<Result<_, _>>()?)
+}
+
+fn process<T: Sub + Default + Debug>(commands: &[Command]) -> u32 {
+    let sub = commands.iter().fold(T::default(), |sub, cmd| match *cmd {
+        Command(Direction::Forward, amt) => sub.forward(amt as _),
+        Command(Direction::Up, amt) => sub.depth(-(amt as i16)),
+        Command(Direction::Down, amt) => sub.depth(amt as _),
+    });
+    log::info!("Sub: {:?}", sub);
+    sub.end_state()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, process};
+    use crate::sub::*;
+
+    const DATA: &str = r"
+forward 5
+down 5
+forward 8
+up 3
+down 8
+forward 2
+";
+
+    #[test]
+    fn test_directions() {
+        use crate::Direction::*;
+        assert!(matches!("forward".parse(), Ok(Forward)));
+        assert!(matches!(" up ".parse(), Ok(Up)));
+        assert!(matches!("down".parse(), Ok(Down)));
+        assert!("backward".parse::<crate::Direction>().is_err());
+    }
+
+    #[test]
+    fn test_stage1() {
+        let res = process::<Submarine>(&parse(DATA).unwrap());
+        assert_eq!(150, res);
+    }
+
+    #[test]
+    fn test_stage2() {
+        let res = process::<SubAim>(&parse(DATA).unwrap());
+        assert_eq!(900, res);
+    }
+
+    #[test]
+    fn test_parsing_errors() {
+        let err = parse("forward 5\nsideways 2\n").unwrap_err();
+        assert_eq!(
+            "line 2, column 1: Invalid value \"sideways\": Unknown command \"sideways\"",
+            err.to_string()
+        );
+        let err = parse("forward 5\ndown\n").unwrap_err();
+        assert_eq!("line 2, column 5: Missing field", err.to_string());
+    }
+}
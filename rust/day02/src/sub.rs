@@ -54,6 +54,6 @@ impl Sub for SubAim {
     }
 
     fn end_state(&self) -> u32 {
-        self.pos as u32 * self.depth as u32
+        self.pos * self.depth
     }
 }
@@ -0,0 +1 @@
+platform::examples!(day02::Day02);
@@ -0,0 +1,70 @@
+//! Random bit matrices, checked against a naive reference.
+
+use platform::{
+    generate::{Generate, Rng},
+    rand::{seq::index, Rng as _},
+    Answer, Stage,
+};
+
+use crate::Day03;
+
+/// Rating of the second stage as the puzzle states it: numbers are filtered one bit position
+/// after the other, keeping those with the most common value at that position for the oxygen
+/// generator, or the least common one for the CO2 scrubber, until a single number is left
+fn rating(numbers: &[&str], oxygen: bool) -> Option<usize> {
+    let mut numbers = numbers.to_vec();
+    let mut position = 0;
+    while numbers.len() > 1 {
+        let bits: Vec<u8> = numbers
+            .iter()
+            .map(|n| n.as_bytes().get(position).copied())
+            .collect::<Option<_>>()?;
+        let count = |value: &u8| bits.iter().filter(|&bit| bit == value).count();
+        let keep = if oxygen {
+            // Ties keep the ones, the last of the equally common values
+            [b'0', b'1'].iter().max_by_key(|&value| count(value))
+        } else {
+            // Ties keep the zeros, the first of the equally rare values. Only the values some
+            // number has are compared: one that none has would leave no number at all.
+            [b'0', b'1']
+                .iter()
+                .filter(|&value| count(value) > 0)
+                .min_by_key(|&value| count(value))
+        }?;
+        numbers.retain(|n| n.as_bytes()[position] == *keep);
+        position += 1;
+    }
+    usize::from_str_radix(numbers.first()?, 2).ok()
+}
+
+impl Generate for Day03 {
+    /// Numbers are distinct, as in the puzzle inputs, so that the second stage ends up with a
+    /// single number
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        let width = rng.gen_range(1..=12);
+        let count = size.min(1 << width);
+        index::sample(rng, 1 << width, count)
+            .into_iter()
+            .map(|n| format!("{:0width$b}\n", n, width = width))
+            .collect()
+    }
+
+    fn reference(&self, data: &str, stage: Stage) -> Option<Answer> {
+        let numbers: Vec<_> = data.lines().filter(|l| !l.is_empty()).collect();
+        let width = numbers.first()?.len();
+        match stage {
+            Stage::Stage1 => {
+                let gamma = (0..width).fold(0, |gamma, i| {
+                    let ones = numbers.iter().filter(|n| n.as_bytes()[i] == b'1').count();
+                    2 * gamma + usize::from(2 * ones > numbers.len())
+                });
+                let epsilon = !gamma & ((1 << width) - 1);
+                Some((gamma * epsilon).into())
+            }
+            Stage::Stage2 => Some((rating(&numbers, true)? * rating(&numbers, false)?).into()),
+            Stage::Custom(_) => None,
+        }
+    }
+}
+
+platform::generated!(Day03);
@@ -0,0 +1,235 @@
+use enum_map::{Enum, EnumMap};
+use platform::{anyhow, grid::Grid, Answer, Challenge};
+use std::{fmt, str::FromStr};
+
+mod generate;
+
+#[derive(Debug, Clone, Enum, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Bit {
+    Z,
+    I,
+}
+
+impl fmt::Display for Bit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Z => write!(f, "0"),
+            Self::I => write!(f, "1"),
+        }
+    }
+}
+
+impl TryFrom<char> for Bit {
+    type Error = anyhow::Error;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        Ok(match value {
+            '0' => Self::Z,
+            '1' => Self::I,
+            _ => anyhow::bail!("Unknown char {:?}", value),
+        })
+    }
+}
+
+impl From<Bit> for usize {
+    fn from(bit: Bit) -> usize {
+        match bit {
+            Bit::Z => 0,
+            Bit::I => 1,
+        }
+    }
+}
+
+impl Bit {
+    fn bin2dec(iter: impl Iterator<Item = Self>) -> usize {
+        iter.fold(0, |num, bit| 2 * num + usize::from(bit))
+    }
+}
+
+pub struct Diagnostic {
+    data: Grid<Bit>,
+}
+
+impl FromStr for Diagnostic {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self {
+            data: Grid::parse_chars(s)?,
+        })
+    }
+}
+
+impl Diagnostic {
+    pub fn power_consumption(&self) -> usize {
+        self.gamma_rate() * self.epsilon_rate()
+    }
+
+    pub fn life_support_rating(&self) -> usize {
+        self.oxygen_rate() * self.co2_rate()
+    }
+
+    pub fn gamma_rate(&self) -> usize {
+        self.stage1_rate(|map| {
+            if map[Bit::I] > map[Bit::Z] {
+                Bit::I
+            } else {
+                Bit::Z
+            }
+        })
+    }
+
+    pub fn epsilon_rate(&self) -> usize {
+        self.stage1_rate(|map| {
+            if map[Bit::I] > map[Bit::Z] {
+                Bit::Z
+            } else {
+                Bit::I
+            }
+        })
+    }
+
+    pub fn oxygen_rate(&self) -> usize {
+        self.stage2_rate(|map| {
+            if map[Bit::I] >= map[Bit::Z] {
+                Bit::I
+            } else {
+                Bit::Z
+            }
+        })
+    }
+
+    pub fn co2_rate(&self) -> usize {
+        self.stage2_rate(|map| {
+            if map[Bit::I] >= map[Bit::Z] {
+                Bit::Z
+            } else {
+                Bit::I
+            }
+        })
+    }
+
+    fn stage1_rate(&self, choose: impl Fn(EnumMap<Bit, usize>) -> Bit) -> usize {
+        Bit::bin2dec((0..self.data.ncols()).map(|i| choose(self.count_bit(i))))
+    }
+
+    fn stage2_rate(&self, choose: impl Fn(EnumMap<Bit, usize>) -> Bit) -> usize {
+        let mut numbers: Vec<_> = self.data.rows().collect();
+
+        for i in 0..self.data.ncols() {
+            if numbers.len() <= 1 {
+                break;
+            }
+            let counts = numbers.iter().fold(EnumMap::default(), |mut map, row| {
+                map[row[i]] += 1;
+                map
+            });
+            // When every number has the same bit, it is both the most and the least common one
+            if counts.values().any(|&count| count == 0) {
+                continue;
+            }
+            let keep = choose(counts);
+            numbers.retain(|row| row[i] == keep);
+        }
+        match numbers[..] {
+            [number] => Bit::bin2dec(number.iter().copied()),
+            _ => 0,
+        }
+    }
+
+    fn count_bit(&self, bit: usize) -> EnumMap<Bit, usize> {
+        self.data
+            .column(bit)
+            .fold(EnumMap::default(), |mut map, bit| {
+                map[*bit] += 1;
+                map
+            })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Day03;
+
+impl Challenge for Day03 {
+    const DAY: u8 = 3;
+    type Input = Diagnostic;
+
+    fn parse(&self, data: &str) -> anyhow::Result<Self::Input> {
+        data.parse()
+    }
+
+    fn stage1(&self, input: &Self::Input) -> anyhow::Result<Answer> {
+        Ok(input.power_consumption().into())
+    }
+
+    fn stage2(&self, input: &Self::Input) -> anyhow::Result<Answer> {
+        Ok(input.life_support_rating().into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Bit;
+
+    use super::Diagnostic;
+
+    const TEST_DATA: &str = r"00100
+11110
+10110
+10111
+10101
+01111
+00111
+11100
+10000
+11001
+00010
+01010
+";
+
+    #[test]
+    fn test_data_parsing() {
+        let diag: Diagnostic = TEST_DATA.parse().unwrap();
+        assert_eq!((12, 5), diag.data.dim());
+    }
+
+    #[test]
+    fn test_parsing_errors() {
+        let err = "0101\n011\n".parse::<Diagnostic>().err().unwrap();
+        assert_eq!(
+            "line 2, column 1: Row has 3 cells, expected 4",
+            err.to_string()
+        );
+        let err = "01\n0é\n".parse::<Diagnostic>().err().unwrap();
+        assert_eq!("line 2, column 2: Unknown char 'é'", err.to_string());
+        assert!("\n".parse::<Diagnostic>().is_err());
+    }
+
+    #[test]
+    fn test_bit_counting() {
+        let diag: Diagnostic = TEST_DATA.parse().unwrap();
+        let counts = diag.count_bit(0);
+        assert_eq!(counts[Bit::Z], 5);
+        assert_eq!(counts[Bit::I], 7);
+    }
+
+    #[test]
+    fn test_stage1() {
+        let diag: Diagnostic = TEST_DATA.parse().unwrap();
+        let gamma = diag.gamma_rate();
+        let epsilon = diag.epsilon_rate();
+
+        assert_eq!(gamma, 22);
+        assert_eq!(epsilon, 9);
+    }
+
+    #[test]
+    fn test_stage2() {
+        let diag: Diagnostic = TEST_DATA.parse().unwrap();
+        let oxygen = diag.oxygen_rate();
+        let co2 = diag.co2_rate();
+
+        assert_eq!(oxygen, 23);
+        assert_eq!(co2, 10);
+    }
+}
@@ -0,0 +1 @@
+platform::examples!(day03::Day03);
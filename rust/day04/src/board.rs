@@ -1,91 +1,69 @@
 use std::str::FromStr;
 
-use ndarray::{Array1, Array2, ArrayView2, Axis, Zip};
-use platform::anyhow;
+use platform::{
+    grid::Grid,
+    parse::{ParseError, Text},
+};
 #[derive(Debug, Clone)]
 pub struct Board {
-    data: Array2<u32>,
+    data: Grid<u32>,
 }
 
 impl FromStr for Board {
-    type Err = anyhow::Error;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let data = combine_rows(
-            s.lines()
-                .map(str::trim)
-                .filter(|s| !s.is_empty())
-                .map(|l| {
-                    l.split_whitespace()
-                        .map(|s| s.parse())
-                        .collect::<Result<Array1<_>, _>>()
-                })
-                .collect::<Result<Vec<_>, _>>()?,
-        );
-        Ok(Self { data })
+        Self::parse(Text::new(s))
     }
 }
 
 impl Board {
+    /// Parses a board out of a block of the data file, so that errors point into the whole file
+    pub fn parse(text: Text) -> Result<Self, ParseError> {
+        Ok(Self {
+            data: Grid::parse_numbers(text)?,
+        })
+    }
+
+    #[cfg(test)]
     pub fn shape(&self) -> (usize, usize) {
         self.data.dim()
     }
 }
 
-fn combine_rows<T: Clone>(arrays: impl IntoIterator<Item = Array1<T>>) -> Array2<T> {
-    arrays
-        .into_iter()
-        .fold(None::<Array2<T>>, |arr, row| {
-            let row = row.broadcast((1, row.dim())).unwrap();
-            if let Some(mut arr) = arr {
-                arr.append(Axis(0), row).unwrap();
-                Some(arr)
-            } else {
-                Some(row.to_owned())
-            }
-        })
-        .unwrap_or_else(|| Array2::from_shape_fn((0, 0), |_| unreachable!()))
-}
-
 #[derive(Debug, Clone)]
 pub struct Player {
     bingo: Board,
-    draws: Array2<bool>,
+    draws: Grid<bool>,
 }
 
 impl From<Board> for Player {
     fn from(bingo: Board) -> Self {
-        let draws = Array2::from_elem(bingo.data.dim(), false);
+        let draws = Grid::from_elem(bingo.data.dim(), false);
         Self { bingo, draws }
     }
 }
 
 impl Player {
+    #[cfg(test)]
     pub fn board(&self) -> &Board {
         &self.bingo
     }
 
     pub fn winning(&self) -> bool {
-        self.draws.rows().into_iter().any(|r| r.iter().all(|&b| b))
-            || self
-                .draws
-                .columns()
-                .into_iter()
-                .any(|c| c.into_iter().all(|&b| b))
+        self.draws.rows().any(|r| r.iter().all(|&b| b))
+            || self.draws.columns().any(|c| c.iter().all(|&b| b))
     }
     pub fn draw(&mut self, number: u32) {
+        for pos in self.bingo.data.positions(|&v| v == number) {
+            self.draws[pos] = true;
+        }
+    }
+
+    pub(crate) fn unmarked(&self) -> impl '_ + Iterator<Item = u32> {
         self.bingo
             .data
             .indexed_iter()
-            .filter_map(|((i, j), &v)| if v == number { Some((i, j)) } else { None })
-            .for_each(|(i, j)| self.draws[(i, j)] = true)
-    }
-
-    pub(crate) fn unmarked(&self) -> impl Iterator<Item = u32> {
-        Zip::from(self.bingo.data.view())
-            .and(self.draws.view())
-            .map_collect(|&v, &b| if !b { Some(v) } else { None })
-            .into_iter()
-            .filter_map(|x| x)
+            .filter_map(|(pos, &v)| if !self.draws[pos] { Some(v) } else { None })
     }
 }
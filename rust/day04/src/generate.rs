@@ -0,0 +1,92 @@
+//! Random bingo games, checked against a naive reference.
+
+use platform::{
+    generate::{Generate, Rng},
+    rand::{seq::index, seq::SliceRandom, Rng as _},
+    Answer, Stage,
+};
+
+use crate::Day04;
+
+/// Size of the square boards
+const SIDE: usize = 5;
+
+/// Scores of the boards in the order they win, grouped by the draw making them win
+fn scores(data: &str) -> Option<Vec<Vec<u32>>> {
+    let mut paragraphs = data.split("\n\n");
+    let draws = paragraphs
+        .next()?
+        .trim()
+        .split(',')
+        .map(|n| n.parse().ok())
+        .collect::<Option<Vec<u32>>>()?;
+    let mut boards = paragraphs
+        .map(|board| {
+            board
+                .lines()
+                .filter(|l| !l.trim().is_empty())
+                .map(|l| l.split_whitespace().map(|n| n.parse().ok()).collect())
+                .collect::<Option<Vec<Vec<u32>>>>()
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    let mut marked = Vec::new();
+    let mut scores = Vec::new();
+    for draw in draws {
+        marked.push(draw);
+        let is_marked = |n: &u32| marked.contains(n);
+        let (won, playing): (Vec<_>, Vec<_>) = boards.into_iter().partition(|board| {
+            let row = board.iter().any(|row| row.iter().all(is_marked));
+            let column = (0..board.first().map_or(0, Vec::len))
+                .any(|j| board.iter().all(|row| row.get(j).is_some_and(is_marked)));
+            row || column
+        });
+        boards = playing;
+        if !won.is_empty() {
+            let unmarked = |board: &Vec<Vec<u32>>| -> u32 {
+                board.iter().flatten().filter(|n| !is_marked(n)).sum()
+            };
+            scores.push(won.iter().map(|board| unmarked(board) * draw).collect());
+        }
+    }
+    Some(scores)
+}
+
+impl Generate for Day04 {
+    /// Every number is drawn, so that every board eventually wins, and numbers are distinct
+    /// within a board, as in the puzzle inputs
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        let pool = rng.gen_range(SIDE * SIDE..SIDE * SIDE + 5 * size);
+        let mut draws: Vec<_> = (0..pool).collect();
+        draws.shuffle(rng);
+        let draws: Vec<_> = draws.iter().map(usize::to_string).collect();
+
+        let mut data = draws.join(",");
+        for _ in 0..size.div_ceil(3) {
+            data += "\n";
+            let numbers = index::sample(rng, pool, SIDE * SIDE).into_vec();
+            for row in numbers.chunks(SIDE) {
+                let row: Vec<_> = row.iter().map(|n| format!("{:>2}", n)).collect();
+                data += &format!("\n{}", row.join(" "));
+            }
+        }
+        data + "\n"
+    }
+
+    /// There is no reference answer when several boards win on the same draw, the puzzle not
+    /// telling which one to pick
+    fn reference(&self, data: &str, stage: Stage) -> Option<Answer> {
+        let scores = scores(data)?;
+        let winners = match stage {
+            Stage::Stage1 => scores.first()?,
+            Stage::Stage2 => scores.last()?,
+            Stage::Custom(_) => return None,
+        };
+        match winners[..] {
+            [score] => Some(score.into()),
+            _ => None,
+        }
+    }
+}
+
+platform::generated!(Day04);
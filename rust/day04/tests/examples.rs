@@ -0,0 +1 @@
+platform::examples!(day04::Day04);
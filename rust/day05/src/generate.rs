@@ -0,0 +1,77 @@
+//! Random vents, checked against a naive reference.
+
+use platform::{
+    generate::{Generate, Rng},
+    rand::Rng as _,
+    Answer, Stage,
+};
+
+use crate::Day05;
+
+/// Vents stay within a small area, so that they often overlap
+const AREA: i32 = 16;
+
+/// Number of points covered by at least two vents, only counting diagonal vents when `diagonals`
+fn overlaps(data: &str, diagonals: bool) -> Option<usize> {
+    let mut covered = vec![vec![0; AREA as usize]; AREA as usize];
+    for line in data.lines().filter(|l| !l.trim().is_empty()) {
+        let (a, b) = line.split_once(" -> ")?;
+        let point = |p: &str| -> Option<(i32, i32)> {
+            let (x, y) = p.trim().split_once(',')?;
+            Some((x.parse().ok()?, y.parse().ok()?))
+        };
+        let ((x1, y1), (x2, y2)) = (point(a)?, point(b)?);
+        if x1 != x2 && y1 != y2 && !diagonals {
+            continue;
+        }
+        let (dx, dy) = ((x2 - x1).signum(), (y2 - y1).signum());
+        let length = (x2 - x1).abs().max((y2 - y1).abs());
+        for i in 0..=length {
+            let (x, y) = (x1 + i * dx, y1 + i * dy);
+            *covered.get_mut(y as usize)?.get_mut(x as usize)? += 1;
+        }
+    }
+    Some(
+        covered
+            .iter()
+            .flatten()
+            .filter(|&&count| count >= 2)
+            .count(),
+    )
+}
+
+impl Generate for Day05 {
+    /// Vents are horizontal, vertical or diagonal at 45 degrees, as in the puzzle inputs
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        let mut data = String::new();
+        for _ in 0..size {
+            let (x1, y1) = (rng.gen_range(0..AREA), rng.gen_range(0..AREA));
+            let (x2, y2) = match rng.gen_range(0..3) {
+                0 => (rng.gen_range(0..AREA), y1),
+                1 => (x1, rng.gen_range(0..AREA)),
+                _ => {
+                    let (dx, dy) = (
+                        if rng.gen() { 1 } else { -1 },
+                        if rng.gen() { 1 } else { -1 },
+                    );
+                    // Longest diagonal staying within the area in that direction
+                    let room = |p: i32, d: i32| if d > 0 { AREA - 1 - p } else { p };
+                    let length = rng.gen_range(0..=room(x1, dx).min(room(y1, dy)));
+                    (x1 + length * dx, y1 + length * dy)
+                }
+            };
+            data += &format!("{},{} -> {},{}\n", x1, y1, x2, y2);
+        }
+        data
+    }
+
+    fn reference(&self, data: &str, stage: Stage) -> Option<Answer> {
+        match stage {
+            Stage::Stage1 => overlaps(data, false).map(Answer::from),
+            Stage::Stage2 => overlaps(data, true).map(Answer::from),
+            Stage::Custom(_) => None,
+        }
+    }
+}
+
+platform::generated!(Day05);
@@ -0,0 +1,225 @@
+use std::{collections::HashMap, str::FromStr};
+
+use geometry::{Aabb, Segment, Vec2};
+use platform::{
+    anyhow,
+    parse::{ParseError, Pattern, Text},
+    Answer, Challenge, CustomStage, Stage,
+};
+
+mod generate;
+
+pub struct VentList {
+    vents: Vec<Segment<u32>>,
+}
+
+/// Layout of a vent, from its start to its end point
+const VENT: Pattern = Pattern::new("{},{} -> {},{}");
+
+impl FromStr for VentList {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let vents = Text::new(s)
+            .lines()
+            .map(|line| {
+                let [x1, y1, x2, y2] = VENT.parse(line)?;
+                Ok(Segment::new(Vec2::new(x1, y1), Vec2::new(x2, y2)))
+            })
+            .collect::<Result<_, ParseError>>()?;
+        Ok(Self { vents })
+    }
+}
+
+impl VentList {
+    /// Smallest box containing every vent
+    fn bounds(&self) -> Option<Aabb<u32>> {
+        Aabb::from_points(self.vents.iter().flat_map(|line| [line.a, line.b]))
+    }
+
+    fn isect_stage1(&self) -> usize {
+        let bounds = match self.bounds() {
+            Some(bounds) => bounds,
+            None => return 0,
+        };
+
+        let straight = self
+            .vents
+            .iter()
+            .filter(|l| l.is_horizontal() || l.is_vertical())
+            .collect::<Vec<_>>();
+
+        bounds
+            .lattice_points()
+            .filter(|vec| {
+                if straight.iter().filter(|l| l.bounds().contains(vec)).count() >= 2 {
+                    log::info!("{:?} intersects lines", vec);
+                    true
+                } else {
+                    false
+                }
+            })
+            .count()
+    }
+
+    /// Same as [`VentList::isect_stage1`], drawing every straight vent point by point instead of
+    /// testing every point of the boundary against every vent
+    fn isect_stage1_rasterized(&self) -> usize {
+        let mut covered = HashMap::new();
+        for line in self.vents.iter() {
+            if !(line.is_horizontal() || line.is_vertical()) {
+                continue;
+            }
+            for point in line.lattice_points() {
+                *covered.entry(point).or_insert(0) += 1;
+            }
+        }
+        covered.values().filter(|&&count| count >= 2).count()
+    }
+
+    /// Draws the number of straight vents covering each point, from the origin to the farthest
+    /// point of the vents, as in the puzzle statement
+    fn diagram(&self) -> String {
+        let max = match self.bounds() {
+            Some(bounds) => bounds.max,
+            None => return String::new(),
+        };
+        let straight = self
+            .vents
+            .iter()
+            .filter(|l| l.is_horizontal() || l.is_vertical())
+            .collect::<Vec<_>>();
+
+        let mut diagram = String::new();
+        for y in 0..=max.y {
+            for x in 0..=max.x {
+                let point = Vec2::new(x, y);
+                let count = straight
+                    .iter()
+                    .filter(|l| l.bounds().contains(&point))
+                    .count();
+                diagram.push(match count {
+                    0 => '.',
+                    n => std::char::from_digit(n.min(9) as u32, 10).unwrap(),
+                });
+            }
+            diagram.push('\n');
+        }
+        diagram
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Day05;
+
+impl Challenge for Day05 {
+    const DAY: u8 = 5;
+    type Input = VentList;
+
+    const CUSTOM_STAGES: &'static [CustomStage] = &[
+        CustomStage::new(
+            "visualize",
+            "Draws the diagram of the horizontal and vertical vents",
+        ),
+        CustomStage::variant(
+            Stage::Stage1,
+            "stage1-rasterize",
+            "Counts the overlaps by drawing the vents point by point",
+        ),
+    ];
+
+    fn parse(&self, data: &str) -> anyhow::Result<Self::Input> {
+        VentList::from_str(data)
+    }
+
+    fn stage1(&self, input: &Self::Input) -> anyhow::Result<Answer> {
+        Ok(input.isect_stage1().into())
+    }
+    fn stage2(&self, _input: &Self::Input) -> anyhow::Result<Answer> {
+        todo!()
+    }
+
+    fn custom(&self, name: &str, input: &Self::Input) -> anyhow::Result<Answer> {
+        match name {
+            "visualize" => Ok(Answer::text(input.diagram())),
+            "stage1-rasterize" => Ok(input.isect_stage1_rasterized().into()),
+            _ => anyhow::bail!("unknown custom stage {}", name),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use test_log::test;
+
+    use geometry::{Segment, Vec2};
+    use platform::{Answer, Challenge};
+
+    use crate::{Day05, VentList};
+
+    const TEST_DATA: &str = r"0,9 -> 5,9
+8,0 -> 0,8
+9,4 -> 3,4
+2,2 -> 2,1
+7,0 -> 7,4
+6,4 -> 2,0
+0,9 -> 2,9
+3,4 -> 1,4
+0,0 -> 8,8
+5,5 -> 8,2";
+
+    #[test]
+    fn test_parsing() {
+        let ventlist = VentList::from_str(TEST_DATA).unwrap();
+        let vents = &ventlist.vents[..2];
+        let expected = &[
+            Segment::new(Vec2::new(0, 9), Vec2::new(5, 9)),
+            Segment::new(Vec2::new(8, 0), Vec2::new(0, 8)),
+        ];
+
+        assert_eq!(vents, expected);
+    }
+
+    #[test]
+    fn test_parsing_errors() {
+        let err = VentList::from_str("0,9 -> 5,9\n8,0 -> 0\n").err().unwrap();
+        assert_eq!("line 2, column 9: Expected \",\"", err.to_string());
+        let err = VentList::from_str("0,9 -> 5,99999999999\n").err().unwrap();
+        assert!(err
+            .to_string()
+            .starts_with("line 1, column 10: Invalid value \"99999999999\""));
+    }
+
+    #[test]
+    fn test_stage1() {
+        let ventlist = VentList::from_str(TEST_DATA).unwrap();
+
+        assert_eq!(5, ventlist.isect_stage1());
+        assert_eq!(5, ventlist.isect_stage1_rasterized());
+    }
+
+    #[test]
+    fn test_stage1_overlaps() {
+        let count = |data: &str| VentList::from_str(data).unwrap().isect_stage1();
+        // A single vent does not overlap anything
+        assert_eq!(0, count("0,0 -> 3,0"));
+        // Overlaps on the edge of the covered area count
+        assert_eq!(1, count("0,0 -> 2,0\n2,0 -> 2,2"));
+        assert_eq!(2, count("0,2 -> 3,2\n2,2 -> 3,2"));
+        // Diagonal vents are ignored
+        assert_eq!(0, count("0,0 -> 2,2\n0,2 -> 2,0"));
+    }
+
+    #[test]
+    fn test_custom() {
+        let ventlist = VentList::from_str(TEST_DATA).unwrap();
+
+        assert_eq!(
+            Answer::Integer(5),
+            Day05.custom("stage1-rasterize", &ventlist).unwrap()
+        );
+        let err = Day05.custom("stage2-rasterize", &ventlist).unwrap_err();
+        assert_eq!("unknown custom stage stage2-rasterize", err.to_string());
+    }
+}
@@ -0,0 +1 @@
+platform::examples!(day05::Day05);
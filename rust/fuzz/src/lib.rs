@@ -0,0 +1,24 @@
+//! Fuzz targets of the input parsers, run from the `rust/` directory with
+//! `cargo +nightly fuzz run <target>`. Every target starts from the inputs of
+//! `corpus/<target>/`, seeded with the data files and examples of the days.
+
+use std::{fmt::Display, str::FromStr};
+
+/// Parses `data` as a `T`, which must either succeed or fail with a descriptive error, but never
+/// panic. Data which is not valid UTF-8 is skipped, the parsers working on text.
+pub fn parse<T: FromStr>(data: &[u8])
+where
+    T::Err: Display,
+{
+    let text = match std::str::from_utf8(data) {
+        Ok(text) => text,
+        Err(_) => return,
+    };
+    if let Err(err) = text.parse::<T>() {
+        assert!(
+            !err.to_string().trim().is_empty(),
+            "Empty error parsing {:?}",
+            text
+        );
+    }
+}
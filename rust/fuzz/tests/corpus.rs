@@ -0,0 +1,24 @@
+//! Replays the corpus of every target, so that inputs found while fuzzing keep being checked
+//! without a nightly toolchain.
+
+use std::path::Path;
+
+fn replay(target: &str, parse: fn(&[u8])) {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("corpus")
+        .join(target);
+    for entry in std::fs::read_dir(&dir).unwrap() {
+        let path = entry.unwrap().path();
+        parse(&std::fs::read(&path).unwrap());
+    }
+}
+
+#[test]
+fn test_corpus() {
+    replay("command", fuzz::parse::<day02::Command>);
+    replay("direction", fuzz::parse::<day02::Direction>);
+    replay("diagnostic", fuzz::parse::<day03::Diagnostic>);
+    replay("board", fuzz::parse::<day04::Board>);
+    replay("game", fuzz::parse::<day04::Game>);
+    replay("vent_list", fuzz::parse::<day05::VentList>);
+}
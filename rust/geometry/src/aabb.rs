@@ -0,0 +1,171 @@
+use num_traits::PrimInt;
+
+use crate::{inclusive, Vec2};
+
+/// Axis-aligned box, including its edges
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Aabb<T> {
+    pub min: Vec2<T>,
+    pub max: Vec2<T>,
+}
+
+fn min<T: PartialOrd>(a: T, b: T) -> T {
+    if b < a {
+        b
+    } else {
+        a
+    }
+}
+
+fn max<T: PartialOrd>(a: T, b: T) -> T {
+    if b > a {
+        b
+    } else {
+        a
+    }
+}
+
+impl<T: Copy + PartialOrd> Aabb<T> {
+    /// Box with opposite corners `a` and `b`, in any order
+    pub fn new(a: Vec2<T>, b: Vec2<T>) -> Self {
+        Self {
+            min: Vec2::new(min(a.x, b.x), min(a.y, b.y)),
+            max: Vec2::new(max(a.x, b.x), max(a.y, b.y)),
+        }
+    }
+
+    /// Smallest box containing every point, `None` without points
+    pub fn from_points(points: impl IntoIterator<Item = Vec2<T>>) -> Option<Self> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        Some(points.fold(Self::new(first, first), |aabb, p| aabb.extended(p)))
+    }
+
+    /// Smallest box containing both this box and `p`
+    pub fn extended(&self, p: Vec2<T>) -> Self {
+        self.union(&Self::new(p, p))
+    }
+
+    /// Smallest box containing both boxes
+    pub fn union(&self, other: &Self) -> Self {
+        Self {
+            min: Vec2::new(min(self.min.x, other.min.x), min(self.min.y, other.min.y)),
+            max: Vec2::new(max(self.max.x, other.max.x), max(self.max.y, other.max.y)),
+        }
+    }
+
+    /// Box of the points in both boxes, `None` when they do not overlap
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let low = Vec2::new(max(self.min.x, other.min.x), max(self.min.y, other.min.y));
+        let high = Vec2::new(min(self.max.x, other.max.x), min(self.max.y, other.max.y));
+        (low.x <= high.x && low.y <= high.y).then_some(Self {
+            min: low,
+            max: high,
+        })
+    }
+
+    pub fn contains(&self, p: &Vec2<T>) -> bool {
+        self.min.x <= p.x && p.x <= self.max.x && self.min.y <= p.y && p.y <= self.max.y
+    }
+}
+
+impl<T: PrimInt> Aabb<T> {
+    /// Points with integer coordinates in the box, row by row
+    pub fn lattice_points(&self) -> impl Iterator<Item = Vec2<T>> {
+        let xs = inclusive(self.min.x, self.max.x);
+        inclusive(self.min.y, self.max.y)
+            .flat_map(move |y| xs.clone().map(move |x| Vec2::new(x, y)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Aabb;
+    use crate::Vec2;
+
+    #[test]
+    fn test_new() {
+        let aabb = Aabb::new(Vec2::new(5, 1), Vec2::new(2, 4));
+        assert_eq!(Vec2::new(2, 1), aabb.min);
+        assert_eq!(Vec2::new(5, 4), aabb.max);
+        assert_eq!(aabb, Aabb::new(aabb.max, aabb.min));
+    }
+
+    #[test]
+    fn test_from_points() {
+        let points = [
+            Vec2::new(0, 9),
+            Vec2::new(5, 9),
+            Vec2::new(8, 0),
+            Vec2::new(2, 4),
+        ];
+        assert_eq!(
+            Some(Aabb::new(Vec2::new(0, 0), Vec2::new(8, 9))),
+            Aabb::from_points(points)
+        );
+        assert_eq!(
+            Some(Aabb::new(Vec2::new(3, 3), Vec2::new(3, 3))),
+            Aabb::from_points([Vec2::new(3, 3)])
+        );
+        assert_eq!(None, Aabb::<u32>::from_points([]));
+        assert_eq!(
+            Some(Aabb::new(Vec2::new(-0.5, 1.), Vec2::new(2., 3.5))),
+            Aabb::from_points([Vec2::new(2., 1.), Vec2::new(-0.5, 3.5)])
+        );
+    }
+
+    #[test]
+    fn test_contains() {
+        let aabb = Aabb::new(Vec2::new(1, 1), Vec2::new(3, 2));
+        assert!(aabb.contains(&Vec2::new(1, 1)));
+        assert!(aabb.contains(&Vec2::new(3, 2)));
+        assert!(aabb.contains(&Vec2::new(2, 2)));
+        assert!(!aabb.contains(&Vec2::new(0, 1)));
+        assert!(!aabb.contains(&Vec2::new(2, 3)));
+        assert!(aabb.extended(Vec2::new(2, 3)).contains(&Vec2::new(2, 3)));
+    }
+
+    #[test]
+    fn test_union_and_intersection() {
+        let a = Aabb::new(Vec2::new(0, 0), Vec2::new(4, 4));
+        let b = Aabb::new(Vec2::new(2, 3), Vec2::new(6, 5));
+        assert_eq!(Aabb::new(Vec2::new(0, 0), Vec2::new(6, 5)), a.union(&b));
+        assert_eq!(
+            Some(Aabb::new(Vec2::new(2, 3), Vec2::new(4, 4))),
+            a.intersection(&b)
+        );
+        assert_eq!(Some(a), a.intersection(&a));
+
+        // Touching edges overlap, boxes apart do not
+        let c = Aabb::new(Vec2::new(4, 0), Vec2::new(5, 1));
+        assert_eq!(
+            Some(Aabb::new(Vec2::new(4, 0), Vec2::new(4, 1))),
+            a.intersection(&c)
+        );
+        assert_eq!(None, b.intersection(&c));
+    }
+
+    #[test]
+    fn test_lattice_points() {
+        let aabb = Aabb::new(Vec2::new(1u32, 5), Vec2::new(3, 6));
+        assert_eq!(
+            vec![
+                Vec2::new(1, 5),
+                Vec2::new(2, 5),
+                Vec2::new(3, 5),
+                Vec2::new(1, 6),
+                Vec2::new(2, 6),
+                Vec2::new(3, 6)
+            ],
+            aabb.lattice_points().collect::<Vec<_>>()
+        );
+        let corner = Aabb::new(Vec2::new(u8::MAX, u8::MAX - 1), Vec2::new(u8::MAX, u8::MAX));
+        assert_eq!(2, corner.lattice_points().count());
+        assert_eq!(
+            1,
+            Aabb::new(Vec2::new(-1, -1), Vec2::new(-1, -1))
+                .lattice_points()
+                .count()
+        );
+    }
+}
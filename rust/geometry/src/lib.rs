@@ -0,0 +1,36 @@
+//! Geometry shared by the challenges: vectors, segments and axis-aligned boxes.
+//!
+//! Everything is generic over the type of the coordinates. Predicates which need to subtract
+//! coordinates, such as [`Segment::intersects`], require them to be signed, while iterating over
+//! lattice points works with any primitive integer, unsigned ones included.
+
+mod aabb;
+mod segment;
+mod vec;
+
+pub use aabb::Aabb;
+pub use segment::Segment;
+pub use vec::{orientation, Orientation, Vec2, Vec3};
+
+/// Integers from `start` to `end` included, without overflowing when `end` is the largest value
+fn inclusive<T: num_traits::PrimInt>(start: T, end: T) -> impl Iterator<Item = T> + Clone {
+    std::iter::successors((start <= end).then_some(start), move |&i| {
+        (i < end).then(|| i + T::one())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::inclusive;
+
+    #[test]
+    fn test_inclusive() {
+        assert_eq!(vec![2, 3, 4], inclusive(2, 4).collect::<Vec<_>>());
+        assert_eq!(vec![7], inclusive(7, 7).collect::<Vec<_>>());
+        assert_eq!(0, inclusive(5, 4).count());
+        assert_eq!(
+            vec![254, 255],
+            inclusive(254u8, u8::MAX).collect::<Vec<_>>()
+        );
+    }
+}
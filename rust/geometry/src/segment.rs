@@ -0,0 +1,185 @@
+use num_traits::{PrimInt, Signed};
+
+use crate::{inclusive, orientation, Aabb, Vec2};
+
+/// Segment between two points, both included
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Segment<T> {
+    pub a: Vec2<T>,
+    pub b: Vec2<T>,
+}
+
+impl<T> Segment<T> {
+    pub fn new(a: Vec2<T>, b: Vec2<T>) -> Self {
+        Self { a, b }
+    }
+}
+
+impl<T: Copy + PartialOrd> Segment<T> {
+    pub fn is_horizontal(&self) -> bool {
+        self.a.y == self.b.y
+    }
+
+    pub fn is_vertical(&self) -> bool {
+        self.a.x == self.b.x
+    }
+
+    /// Smallest box containing the segment
+    pub fn bounds(&self) -> Aabb<T> {
+        Aabb::new(self.a, self.b)
+    }
+}
+
+impl<T: Copy + Signed + PartialOrd> Segment<T> {
+    /// Whether `p` lies on the segment
+    pub fn contains(&self, p: &Vec2<T>) -> bool {
+        orientation(&self.a, &self.b, p).is_none() && self.bounds().contains(p)
+    }
+
+    /// Whether both segments have at least a point in common
+    pub fn intersects(&self, other: &Self) -> bool {
+        let o1 = orientation(&self.a, &self.b, &other.a);
+        let o2 = orientation(&self.a, &self.b, &other.b);
+        let o3 = orientation(&other.a, &other.b, &self.a);
+        let o4 = orientation(&other.a, &other.b, &self.b);
+
+        if let (Some(a), Some(b), Some(c), Some(d)) = (o1, o2, o3, o4) {
+            if a != b && c != d {
+                return true;
+            }
+        }
+        // An end aligned with the other segment may lie on it
+        (o1.is_none() && self.bounds().contains(&other.a))
+            || (o2.is_none() && self.bounds().contains(&other.b))
+            || (o3.is_none() && other.bounds().contains(&self.a))
+            || (o4.is_none() && other.bounds().contains(&self.b))
+    }
+}
+
+fn gcd(a: u128, b: u128) -> u128 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+impl<T: PrimInt> Segment<T> {
+    /// Points with integer coordinates on the segment, from `a` to `b`
+    pub fn lattice_points(&self) -> impl Iterator<Item = Vec2<T>> {
+        let wide = |v: T| v.to_i128().expect("coordinates fit in an i128");
+        let (x, y) = (wide(self.a.x), wide(self.a.y));
+        let (dx, dy) = (wide(self.b.x) - x, wide(self.b.y) - y);
+        let steps = gcd(dx.unsigned_abs(), dy.unsigned_abs()) as i128;
+        let step = match steps {
+            0 => (0, 0),
+            _ => (dx / steps, dy / steps),
+        };
+        // Every point lies between the ends, so that it fits in `T` as well
+        let narrow = |v: i128| T::from(v).expect("points lie between the ends");
+        inclusive(0, steps).map(move |i| Vec2::new(narrow(x + i * step.0), narrow(y + i * step.1)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Segment;
+    use crate::{Aabb, Vec2};
+
+    #[test]
+    fn test_straight() {
+        let horizontal = Segment::new(Vec2::new(9, 4), Vec2::new(3, 4));
+        assert!(horizontal.is_horizontal());
+        assert!(!horizontal.is_vertical());
+        let vertical = Segment::new(Vec2::new(7, 0), Vec2::new(7, 4));
+        assert!(vertical.is_vertical());
+        assert!(!vertical.is_horizontal());
+        let diagonal = Segment::new(Vec2::new(8, 0), Vec2::new(0, 8));
+        assert!(!diagonal.is_horizontal() && !diagonal.is_vertical());
+        assert_eq!(
+            Aabb::new(Vec2::new(3, 4), Vec2::new(9, 4)),
+            horizontal.bounds()
+        );
+    }
+
+    #[test]
+    fn test_contains() {
+        let diagonal = Segment::new(Vec2::new(8, 0), Vec2::new(0, 8));
+        assert!(diagonal.contains(&Vec2::new(8, 0)));
+        assert!(diagonal.contains(&Vec2::new(3, 5)));
+        assert!(!diagonal.contains(&Vec2::new(3, 3)));
+        assert!(!diagonal.contains(&Vec2::new(9, -1)));
+        let horizontal = Segment::new(Vec2::new(0., 1.), Vec2::new(2., 1.));
+        assert!(horizontal.contains(&Vec2::new(1.5, 1.)));
+        assert!(!horizontal.contains(&Vec2::new(2.5, 1.)));
+    }
+
+    #[test]
+    fn test_no_isect() {
+        let l1 = Segment::new(Vec2::new(1, 1), Vec2::new(10, 1));
+        let l2 = Segment::new(Vec2::new(1, 2), Vec2::new(10, 2));
+
+        assert!(!l1.intersects(&l2));
+    }
+
+    #[test]
+    fn test_isect() {
+        let l1 = Segment::new(Vec2::new(10, 0), Vec2::new(0, 10));
+        let l2 = Segment::new(Vec2::new(0, 0), Vec2::new(10, 10));
+
+        assert!(l1.intersects(&l2));
+    }
+
+    #[test]
+    fn test_isect_special_cases() {
+        let l1 = Segment::new(Vec2::new(0, 0), Vec2::new(4, 0));
+        // Touching at an end, from either side
+        let touching = Segment::new(Vec2::new(2, 3), Vec2::new(2, 0));
+        assert!(l1.intersects(&touching));
+        assert!(touching.intersects(&l1));
+        // Aligned, overlapping or not
+        let overlapping = Segment::new(Vec2::new(3, 0), Vec2::new(6, 0));
+        assert!(l1.intersects(&overlapping));
+        assert!(overlapping.intersects(&l1));
+        let apart = Segment::new(Vec2::new(5, 0), Vec2::new(6, 0));
+        assert!(!l1.intersects(&apart));
+        assert!(!apart.intersects(&l1));
+        // Containing the other one
+        let inner = Segment::new(Vec2::new(1, 0), Vec2::new(2, 0));
+        assert!(l1.intersects(&inner));
+        assert!(inner.intersects(&l1));
+        // Crossing its line beyond its end
+        let beyond = Segment::new(Vec2::new(5, -1), Vec2::new(5, 1));
+        assert!(!l1.intersects(&beyond));
+    }
+
+    #[test]
+    fn test_lattice_points() {
+        let points = |s: Segment<u32>| s.lattice_points().collect::<Vec<_>>();
+        assert_eq!(
+            vec![Vec2::new(9, 4), Vec2::new(8, 4), Vec2::new(7, 4)],
+            points(Segment::new(Vec2::new(9, 4), Vec2::new(7, 4)))
+        );
+        assert_eq!(
+            vec![Vec2::new(7, 0), Vec2::new(7, 1), Vec2::new(7, 2)],
+            points(Segment::new(Vec2::new(7, 0), Vec2::new(7, 2)))
+        );
+        assert_eq!(
+            vec![Vec2::new(8, 0), Vec2::new(7, 1), Vec2::new(6, 2)],
+            points(Segment::new(Vec2::new(8, 0), Vec2::new(6, 2)))
+        );
+        assert_eq!(
+            vec![Vec2::new(3, 3)],
+            points(Segment::new(Vec2::new(3, 3), Vec2::new(3, 3)))
+        );
+
+        let steep = Segment::new(Vec2::new(-6i64, 4), Vec2::new(0, 0));
+        assert_eq!(
+            vec![Vec2::new(-6, 4), Vec2::new(-3, 2), Vec2::new(0, 0)],
+            steep.lattice_points().collect::<Vec<_>>()
+        );
+        let edge = Segment::new(Vec2::new(u8::MAX, 0), Vec2::new(u8::MAX - 2, 2));
+        assert_eq!(3, edge.lattice_points().count());
+        assert!(edge.lattice_points().all(|p| p.x >= u8::MAX - 2));
+    }
+}
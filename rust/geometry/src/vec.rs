@@ -0,0 +1,270 @@
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use duplicate::duplicate;
+use num_traits::{Num, Signed};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Vec2<T> {
+    pub x: T,
+    pub y: T,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Vec3<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+#[duplicate(
+    trait_name  fn_name;
+    [AddAssign] [add_assign];
+    [SubAssign] [sub_assign];
+    [MulAssign] [mul_assign];
+    [DivAssign] [div_assign];
+)]
+impl<T: trait_name<T>> trait_name<Self> for Vec2<T> {
+    fn fn_name(&mut self, rhs: Self) {
+        self.x.fn_name(rhs.x);
+        self.y.fn_name(rhs.y);
+    }
+}
+
+#[duplicate(
+    trait_name  fn_name;
+    [AddAssign] [add_assign];
+    [SubAssign] [sub_assign];
+    [MulAssign] [mul_assign];
+    [DivAssign] [div_assign];
+)]
+impl<T: trait_name<T>> trait_name<Self> for Vec3<T> {
+    fn fn_name(&mut self, rhs: Self) {
+        self.x.fn_name(rhs.x);
+        self.y.fn_name(rhs.y);
+        self.z.fn_name(rhs.z);
+    }
+}
+
+#[duplicate(
+    trait_name  fn_name;
+    [MulAssign] [mul_assign];
+    [DivAssign] [div_assign];
+)]
+impl<T: trait_name<T> + Copy> trait_name<T> for Vec2<T> {
+    fn fn_name(&mut self, rhs: T) {
+        self.x.fn_name(rhs);
+        self.y.fn_name(rhs);
+    }
+}
+
+#[duplicate(
+    trait_name  fn_name;
+    [MulAssign] [mul_assign];
+    [DivAssign] [div_assign];
+)]
+impl<T: trait_name<T> + Copy> trait_name<T> for Vec3<T> {
+    fn fn_name(&mut self, rhs: T) {
+        self.x.fn_name(rhs);
+        self.y.fn_name(rhs);
+        self.z.fn_name(rhs);
+    }
+}
+
+#[duplicate(
+    vec_type    trait_name  trait_assign    fn_name fn_name_assign;
+    [Vec2]      [Add]       [AddAssign]     [add]   [add_assign];
+    [Vec2]      [Sub]       [SubAssign]     [sub]   [sub_assign];
+    [Vec2]      [Mul]       [MulAssign]     [mul]   [mul_assign];
+    [Vec2]      [Div]       [DivAssign]     [div]   [div_assign];
+    [Vec3]      [Add]       [AddAssign]     [add]   [add_assign];
+    [Vec3]      [Sub]       [SubAssign]     [sub]   [sub_assign];
+    [Vec3]      [Mul]       [MulAssign]     [mul]   [mul_assign];
+    [Vec3]      [Div]       [DivAssign]     [div]   [div_assign];
+)]
+impl<T: trait_assign<T>> trait_name<Self> for vec_type<T> {
+    type Output = Self;
+    fn fn_name(mut self, rhs: Self) -> Self::Output {
+        self.fn_name_assign(rhs);
+        self
+    }
+}
+
+#[duplicate(
+    vec_type    trait_name  trait_assign    fn_name fn_name_assign;
+    [Vec2]      [Mul]       [MulAssign]     [mul]   [mul_assign];
+    [Vec2]      [Div]       [DivAssign]     [div]   [div_assign];
+    [Vec3]      [Mul]       [MulAssign]     [mul]   [mul_assign];
+    [Vec3]      [Div]       [DivAssign]     [div]   [div_assign];
+)]
+impl<T: trait_assign<T> + Copy> trait_name<T> for vec_type<T> {
+    type Output = Self;
+    fn fn_name(mut self, rhs: T) -> Self::Output {
+        self.fn_name_assign(rhs);
+        self
+    }
+}
+
+impl<T: Neg<Output = T>> Neg for Vec2<T> {
+    type Output = Self;
+    fn neg(self) -> Self::Output {
+        Self::new(-self.x, -self.y)
+    }
+}
+
+impl<T: Neg<Output = T>> Neg for Vec3<T> {
+    type Output = Self;
+    fn neg(self) -> Self::Output {
+        Self::new(-self.x, -self.y, -self.z)
+    }
+}
+
+impl<T> Vec2<T> {
+    pub fn new(x: T, y: T) -> Self {
+        Self { x, y }
+    }
+}
+
+impl<T> Vec3<T> {
+    pub fn new(x: T, y: T, z: T) -> Self {
+        Self { x, y, z }
+    }
+}
+
+impl<T: Copy + Num> Vec2<T> {
+    pub fn dot(&self, other: &Self) -> T {
+        self.x * other.x + self.y * other.y
+    }
+}
+
+impl<T: Copy + Num> Vec3<T> {
+    pub fn dot(&self, other: &Self) -> T {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    pub fn cross(&self, other: &Self) -> Self {
+        Self::new(
+            self.y * other.z - self.z * other.y,
+            self.z * other.x - self.x * other.z,
+            self.x * other.y - self.y * other.x,
+        )
+    }
+}
+
+impl<T: Copy + Signed> Vec2<T> {
+    /// Length of the shortest path to `other` moving along the axes
+    pub fn manhattan(&self, other: &Self) -> T {
+        (self.x - other.x).abs() + (self.y - other.y).abs()
+    }
+}
+
+impl<T: Copy + Signed> Vec3<T> {
+    /// Length of the shortest path to `other` moving along the axes
+    pub fn manhattan(&self, other: &Self) -> T {
+        (self.x - other.x).abs() + (self.y - other.y).abs() + (self.z - other.z).abs()
+    }
+}
+
+/// Direction in which `p`, `q` and `r` turn, `None` when they are aligned. Clockwise assumes the
+/// y axis going up, as in mathematics: it is counterclockwise on screen, with y going down.
+pub fn orientation<T: Copy + Signed + PartialOrd>(
+    p: &Vec2<T>,
+    q: &Vec2<T>,
+    r: &Vec2<T>,
+) -> Option<Orientation> {
+    let val = (q.y - p.y) * (r.x - q.x) - (q.x - p.x) * (r.y - q.y);
+    if val.is_zero() {
+        None
+    } else {
+        use Orientation::*;
+        Some(if val > T::zero() { CW } else { CCW })
+    }
+}
+
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Orientation {
+    CW,
+    CCW,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{orientation, Orientation, Vec2, Vec3};
+
+    #[test]
+    fn test_vec2_ops() {
+        let (a, b) = (Vec2::new(6, 8), Vec2::new(3, 2));
+        assert_eq!(Vec2::new(9, 10), a + b);
+        assert_eq!(Vec2::new(3, 6), a - b);
+        assert_eq!(Vec2::new(18, 16), a * b);
+        assert_eq!(Vec2::new(2, 4), a / b);
+        assert_eq!(Vec2::new(12, 16), a * 2);
+        assert_eq!(Vec2::new(3, 4), a / 2);
+        assert_eq!(Vec2::new(-6, -8), -a);
+
+        let mut c = a;
+        c += b;
+        c -= Vec2::new(1, 1);
+        c *= 2;
+        c /= Vec2::new(4, 3);
+        assert_eq!(Vec2::new(4, 6), c);
+        assert_eq!(Vec2::default(), Vec2::new(0, 0));
+    }
+
+    #[test]
+    fn test_vec3_ops() {
+        let (a, b) = (Vec3::new(6, 8, 10), Vec3::new(3, 2, 5));
+        assert_eq!(Vec3::new(9, 10, 15), a + b);
+        assert_eq!(Vec3::new(3, 6, 5), a - b);
+        assert_eq!(Vec3::new(18, 16, 50), a * b);
+        assert_eq!(Vec3::new(2, 4, 2), a / b);
+        assert_eq!(Vec3::new(12, 16, 20), a * 2);
+        assert_eq!(Vec3::new(3, 4, 5), a / 2);
+        assert_eq!(Vec3::new(-6, -8, -10), -a);
+
+        let mut c = a;
+        c += b;
+        c -= Vec3::new(1, 1, 1);
+        c *= 2;
+        c /= Vec3::new(4, 3, 2);
+        assert_eq!(Vec3::new(4, 6, 14), c);
+    }
+
+    #[test]
+    fn test_products() {
+        assert_eq!(26, Vec2::new(3, 4).dot(&Vec2::new(2, 5)));
+        assert_eq!(0, Vec2::new(1, 0).dot(&Vec2::new(0, 7)));
+        assert_eq!(32, Vec3::new(1, 2, 3).dot(&Vec3::new(4, 5, 6)));
+
+        let (x, y) = (Vec3::new(1, 0, 0), Vec3::new(0, 1, 0));
+        assert_eq!(Vec3::new(0, 0, 1), x.cross(&y));
+        assert_eq!(Vec3::new(0, 0, -1), y.cross(&x));
+        assert_eq!(
+            Vec3::new(-3, 6, -3),
+            Vec3::new(1, 2, 3).cross(&Vec3::new(4, 5, 6))
+        );
+        assert_eq!(2.5, Vec2::new(0.5, 1.).dot(&Vec2::new(1., 2.)));
+    }
+
+    #[test]
+    fn test_manhattan() {
+        assert_eq!(7, Vec2::new(1, -2).manhattan(&Vec2::new(-1, 3)));
+        assert_eq!(0, Vec2::new(4, 4).manhattan(&Vec2::new(4, 4)));
+        assert_eq!(9, Vec3::new(1, 2, 3).manhattan(&Vec3::new(-1, 5, 7)));
+    }
+
+    #[test]
+    fn test_orientation() {
+        let (p, q) = (Vec2::new(0, 0), Vec2::new(4, 4));
+        assert_eq!(Some(Orientation::CW), orientation(&p, &q, &Vec2::new(5, 1)));
+        assert_eq!(
+            Some(Orientation::CCW),
+            orientation(&p, &q, &Vec2::new(1, 5))
+        );
+        assert_eq!(None, orientation(&p, &q, &Vec2::new(-2, -2)));
+        assert_eq!(
+            Some(Orientation::CCW),
+            orientation(&Vec2::new(0., 0.), &Vec2::new(1., 0.), &Vec2::new(1., 1.))
+        );
+    }
+}
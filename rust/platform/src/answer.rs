@@ -0,0 +1,230 @@
+use std::{fmt, str::FromStr};
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+/// Answer produced by a challenge stage
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Answer {
+    /// Numeric answer, which is what most puzzles ask for
+    Integer(i128),
+    /// Single-line textual answer
+    String(String),
+    /// Multi-line textual answer, e.g. letters drawn on a grid
+    Text(String),
+}
+
+impl Answer {
+    /// Builds a textual answer, choosing between [`Answer::String`] and [`Answer::Text`]
+    /// depending on whether the text spans several lines.
+    pub fn text(text: impl Into<String>) -> Self {
+        let text: String = text.into();
+        let text = text.trim_end();
+        if text.contains('\n') {
+            Self::Text(text.to_owned())
+        } else {
+            Self::String(text.to_owned())
+        }
+    }
+
+    pub fn as_integer(&self) -> Option<i128> {
+        match self {
+            Self::Integer(i) => Some(*i),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Integer(i) => write!(f, "{}", i),
+            Self::String(s) => write!(f, "{}", s),
+            Self::Text(s) => write!(f, "\n{}", s),
+        }
+    }
+}
+
+/// Whether `value` is beyond the integers of TOML, being serialized as a string of digits
+fn is_wide(value: i128) -> bool {
+    i64::try_from(value).is_err()
+}
+
+impl<'de> Deserialize<'de> for Answer {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Visitor;
+
+        impl<'de> de::Visitor<'de> for Visitor {
+            type Value = Answer;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "an integer or a string")
+            }
+
+            fn visit_i64<E: de::Error>(self, value: i64) -> Result<Answer, E> {
+                Ok(value.into())
+            }
+
+            fn visit_u64<E: de::Error>(self, value: u64) -> Result<Answer, E> {
+                Ok(value.into())
+            }
+
+            fn visit_i128<E: de::Error>(self, value: i128) -> Result<Answer, E> {
+                Ok(value.into())
+            }
+
+            fn visit_u128<E: de::Error>(self, value: u128) -> Result<Answer, E> {
+                i128::try_from(value)
+                    .map(Answer::Integer)
+                    .map_err(|_| E::invalid_value(de::Unexpected::Other("u128"), &self))
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Answer, E> {
+                Ok(match value.parse::<i128>() {
+                    Ok(i) if is_wide(i) => Answer::Integer(i),
+                    _ => value.into(),
+                })
+            }
+        }
+
+        deserializer.deserialize_any(Visitor)
+    }
+}
+
+/// Integers beyond the range of `i64`, which TOML cannot represent and JSON only reads back as
+/// floats, are serialized as strings of digits
+impl Serialize for Answer {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match *self {
+            Self::Integer(i) => {
+                if is_wide(i) {
+                    serializer.collect_str(&i)
+                } else {
+                    serializer.serialize_i64(i as i64)
+                }
+            }
+            Self::String(ref s) | Self::Text(ref s) => serializer.serialize_str(s),
+        }
+    }
+}
+
+macro_rules! impl_from_integer {
+    ($($t: ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(value: $t) -> Self {
+                    Self::Integer(value as i128)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_integer!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize, i128);
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Self::text(value)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(value: &str) -> Self {
+        Self::text(value)
+    }
+}
+
+/// Parses an answer given on the command line, as an integer when possible
+impl FromStr for Answer {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.trim().parse::<i128>() {
+            Ok(value) => Self::Integer(value),
+            Err(_) => Self::text(s),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use super::Answer;
+
+    #[test]
+    fn test_from_integer() {
+        assert_eq!(Answer::Integer(150), Answer::from(150u32));
+        assert_eq!(Answer::Integer(-3), Answer::from(-3i16));
+        assert_eq!(Some(7), Answer::from(7usize).as_integer());
+    }
+
+    #[test]
+    fn test_parsing() {
+        assert_eq!(Ok(Answer::Integer(-42)), " -42".parse());
+        assert_eq!(Ok(Answer::from("ABCD")), "ABCD".parse());
+    }
+
+    #[test]
+    fn test_text_kind() {
+        assert_eq!(Answer::String("ABC".into()), Answer::from("ABC"));
+        assert_eq!(Answer::String("ABC".into()), Answer::from("ABC\n"));
+        assert!(matches!(Answer::from("#.#\n.#.\n"), Answer::Text(_)));
+    }
+
+    #[test]
+    fn test_serde() {
+        for answer in [
+            Answer::from(-1924),
+            Answer::from("ABC"),
+            Answer::from("#.#\n.#."),
+        ] {
+            let json = serde_json::to_string(&answer).unwrap();
+            assert_eq!(answer, serde_json::from_str(&json).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_serde_wide_integers() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Manifest {
+            stage1: Answer,
+        }
+
+        for value in [
+            i64::MIN.into(),
+            i64::MAX.into(),
+            u64::MAX.into(),
+            i128::MIN,
+            i128::MAX,
+        ] {
+            let answer = Answer::Integer(value);
+            let json = serde_json::to_string(&answer).unwrap();
+            assert_eq!(answer, serde_json::from_str(&json).unwrap());
+
+            let manifest = Manifest { stage1: answer };
+            let text = toml::to_string(&manifest).unwrap();
+            assert_eq!(manifest, toml::from_str(&text).unwrap());
+        }
+        assert_eq!(
+            "\"18446744073709551615\"",
+            serde_json::to_string(&Answer::from(u64::MAX)).unwrap()
+        );
+        assert_eq!(
+            Answer::from(u64::MAX),
+            serde_json::from_str::<Answer>("18446744073709551615").unwrap()
+        );
+
+        // Only strings out of the range of `i64` are read as integers
+        assert_eq!(
+            Answer::from("123"),
+            serde_json::from_str::<Answer>("\"123\"").unwrap()
+        );
+        assert!(serde_json::from_str::<Answer>("1.5").is_err());
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!("1924", Answer::from(1924).to_string());
+        assert_eq!("\n#.#\n.#.", Answer::from("#.#\n.#.\n").to_string());
+    }
+}
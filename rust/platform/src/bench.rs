@@ -0,0 +1,268 @@
+//! Repeated execution of stages to gather timing statistics.
+
+use std::{
+    fmt,
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use anyhow::Context;
+use serde::{Serialize, Serializer};
+use structopt::StructOpt;
+
+use crate::{
+    exec::guard,
+    memory::{self, Usage},
+    registry::Entry,
+    Answer, Error, Source, Stage,
+};
+
+#[derive(Debug, StructOpt)]
+pub(crate) struct BenchOptions {
+    /// Run the stage repeatedly and report timing statistics instead of a single answer
+    #[structopt(long, name = "iterations")]
+    pub bench: Option<NonZeroUsize>,
+    /// Write the benchmark results as JSON to the given file, `-` meaning stdout
+    #[structopt(long, name = "json file", requires = "iterations")]
+    pub bench_json: Option<PathBuf>,
+}
+
+impl BenchOptions {
+    /// Writes the reports as a JSON array if requested on the command line
+    pub fn write_json(&self, reports: &[Report]) -> anyhow::Result<()> {
+        let path = match &self.bench_json {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+        let json = serde_json::to_string_pretty(reports)?;
+        if path == Path::new("-") {
+            println!("{}", json);
+        } else {
+            std::fs::write(path, json)
+                .with_context(|| format!("Writing benchmark results to {}", path.display()))?;
+        }
+        Ok(())
+    }
+}
+
+/// Summary statistics over a set of timing samples
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Stats {
+    #[serde(rename = "min_ns", serialize_with = "as_nanos")]
+    pub min: Duration,
+    #[serde(rename = "median_ns", serialize_with = "as_nanos")]
+    pub median: Duration,
+    #[serde(rename = "mean_ns", serialize_with = "as_nanos")]
+    pub mean: Duration,
+    #[serde(rename = "stddev_ns", serialize_with = "as_nanos")]
+    pub stddev: Duration,
+}
+
+fn as_nanos<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_u128(duration.as_nanos())
+}
+
+impl Stats {
+    /// Computes the statistics of the samples, or `None` if there are none
+    pub fn from_samples(samples: &[Duration]) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+        let mut sorted = samples.to_vec();
+        sorted.sort_unstable();
+
+        let n = sorted.len();
+        let median = if n.is_multiple_of(2) {
+            (sorted[n / 2 - 1] + sorted[n / 2]) / 2
+        } else {
+            sorted[n / 2]
+        };
+        let mean = sorted.iter().map(Duration::as_secs_f64).sum::<f64>() / n as f64;
+        let variance = if n > 1 {
+            sorted
+                .iter()
+                .map(|d| (d.as_secs_f64() - mean).powi(2))
+                .sum::<f64>()
+                / (n - 1) as f64
+        } else {
+            0.0
+        };
+
+        Some(Self {
+            min: sorted[0],
+            median,
+            mean: Duration::from_secs_f64(mean),
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        })
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "min {:>10.3?}  median {:>10.3?}  mean {:>10.3?}  stddev {:>10.3?}",
+            self.min, self.median, self.mean, self.stddev
+        )
+    }
+}
+
+/// Benchmark results of a single stage
+#[derive(Debug, Clone, Serialize)]
+pub struct Report {
+    pub day: u8,
+    pub stage: Stage,
+    pub iterations: usize,
+    #[serde(serialize_with = "answer_as_string")]
+    pub answer: Answer,
+    /// Statistics of every timed phase in execution order, serialized as a map keyed by phase
+    #[serde(serialize_with = "phases_as_map")]
+    pub phases: Vec<(&'static str, Stats)>,
+    /// Memory usage of the parsing and solving, measured on the last iteration when the counting
+    /// allocator is enabled, see [`memory`]
+    #[serde(
+        serialize_with = "phases_as_map",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub memory: Vec<(&'static str, Usage)>,
+}
+
+fn phases_as_map<S: Serializer, T: Serialize>(
+    phases: &[(&'static str, T)],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_map(phases.iter().map(|(phase, value)| (phase, value)))
+}
+
+fn answer_as_string<S: Serializer>(answer: &Answer, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(answer.to_string().trim_start())
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "day{:02} {}: {} ({} iterations)",
+            self.day, self.stage, self.answer, self.iterations
+        )?;
+        for (phase, stats) in &self.phases {
+            write!(f, "\n  {:<6} {}", phase, stats)?;
+            if let Some((_, usage)) = self.memory.iter().find(|(p, _)| p == phase) {
+                write!(f, "  {}", usage)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Runs `stage` of the challenge `iterations` times, timing the reading of the data file, its
+/// parsing and the solving separately. Standard input can only be read once, so the reading is
+/// not timed for it.
+pub(crate) fn bench(
+    challenge: &dyn Entry,
+    stage: Stage,
+    source: &Source,
+    iterations: NonZeroUsize,
+) -> anyhow::Result<Report> {
+    let _span = tracing::trace_span!("bench", day = challenge.day(), %stage).entered();
+
+    let mut read = Vec::with_capacity(iterations.get());
+    let mut parse = Vec::with_capacity(iterations.get());
+    let mut solve = Vec::with_capacity(iterations.get());
+    let mut answer = None;
+    let mut usage = Default::default();
+    let stdin = match source {
+        Source::Stdin => Some(source.read_blocking()?),
+        Source::File(_) => None,
+    };
+    for _ in 0..iterations.get() {
+        let data = match &stdin {
+            Some(data) => data.clone(),
+            None => {
+                let start = Instant::now();
+                let data = source.read_blocking()?;
+                read.push(start.elapsed());
+                data
+            }
+        };
+
+        // Measuring the memory takes system calls, which are left out of the timings
+        let (input, parse_usage) = memory::measure(|| {
+            let start = Instant::now();
+            let input = guard(|| challenge.parse(&data), Error::Parse);
+            parse.push(start.elapsed());
+            input
+        });
+        let input = input?;
+
+        let (current, solve_usage) = memory::measure(|| {
+            let start = Instant::now();
+            let current = guard(|| challenge.stage(stage, &input), Error::Solve);
+            solve.push(start.elapsed());
+            current
+        });
+        let current = current?;
+        usage = [("parse", parse_usage), ("solve", solve_usage)];
+
+        if let Some(previous) = answer.replace(current) {
+            if Some(&previous) != answer.as_ref() {
+                anyhow::bail!("Stage answered {} then {}", previous, answer.unwrap());
+            }
+        }
+    }
+
+    let phases = [("read", &read), ("parse", &parse), ("solve", &solve)]
+        .into_iter()
+        .filter_map(|(phase, samples)| Some((phase, Stats::from_samples(samples)?)))
+        .collect();
+    Ok(Report {
+        day: challenge.day(),
+        stage,
+        iterations: iterations.get(),
+        answer: answer.expect("at least one iteration"),
+        phases,
+        memory: if memory::is_enabled() {
+            usage.to_vec()
+        } else {
+            Vec::new()
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::Stats;
+
+    fn ms(values: &[u64]) -> Vec<Duration> {
+        values.iter().copied().map(Duration::from_millis).collect()
+    }
+
+    #[test]
+    fn test_stats_odd() {
+        let stats = Stats::from_samples(&ms(&[5, 1, 3])).unwrap();
+
+        assert_eq!(Duration::from_millis(1), stats.min);
+        assert_eq!(Duration::from_millis(3), stats.median);
+        assert_eq!(Duration::from_millis(3), stats.mean);
+        assert_eq!(Duration::from_millis(2), stats.stddev);
+    }
+
+    #[test]
+    fn test_stats_even() {
+        let stats = Stats::from_samples(&ms(&[4, 2, 8, 6])).unwrap();
+
+        assert_eq!(Duration::from_millis(5), stats.median);
+        assert_eq!(Duration::from_millis(5), stats.mean);
+    }
+
+    #[test]
+    fn test_stats_single() {
+        let stats = Stats::from_samples(&ms(&[7])).unwrap();
+
+        assert_eq!(Duration::ZERO, stats.stddev);
+        assert!(Stats::from_samples(&[]).is_none());
+    }
+}
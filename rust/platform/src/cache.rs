@@ -0,0 +1,205 @@
+//! Cache of the answers of previous runs, so that stages are not solved again while neither their
+//! input nor the binary solving them changed.
+//!
+//! The answers are stored next to the running binary, in `<binary>.answers.json`, along with the
+//! fingerprint of the build which produced them: rebuilding the binary discards them.
+
+use std::{
+    collections::{hash_map::DefaultHasher, BTreeMap},
+    hash::{Hash, Hasher},
+    io,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::UNIX_EPOCH,
+};
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use structopt::StructOpt;
+
+use crate::{Answer, Stage};
+
+#[derive(Debug, StructOpt)]
+pub(crate) struct CacheOptions {
+    /// Solve every stage again instead of returning the answers cached by previous runs on the
+    /// same input, leaving the cache untouched
+    #[structopt(long)]
+    pub no_cache: bool,
+}
+
+impl CacheOptions {
+    /// Opens the cache of the running binary, unless it was disabled. The cache being only a
+    /// shortcut, failing to locate it is logged rather than reported.
+    pub fn open(&self) -> Option<Arc<Cache>> {
+        if self.no_cache {
+            return None;
+        }
+        match Cache::for_current_exe() {
+            Ok(cache) => Some(Arc::new(cache)),
+            Err(err) => {
+                tracing::warn!("Answer cache disabled: {:#}", err);
+                None
+            }
+        }
+    }
+}
+
+/// Identifies the answer of a stage on a given input
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct Key(String);
+
+impl Key {
+    /// The input is hashed with the standard hasher, which is not stable across Rust releases.
+    /// That is fine as the entries are discarded as soon as the binary is rebuilt anyway.
+    pub fn new(day: u8, stage: Stage, data: &str) -> Self {
+        let mut hasher = DefaultHasher::new();
+        data.hash(&mut hasher);
+        Self(format!("day{:02}/{}/{:016x}", day, stage, hasher.finish()))
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Contents {
+    fingerprint: String,
+    answers: BTreeMap<String, Answer>,
+}
+
+/// Answers of the current build, shared by the challenges running concurrently
+#[derive(Debug)]
+pub(crate) struct Cache {
+    path: PathBuf,
+    fingerprint: String,
+    answers: Mutex<BTreeMap<String, Answer>>,
+    modified: AtomicBool,
+}
+
+/// Identifies the build of `exe` from its version, size and modification time
+fn fingerprint(exe: &Path) -> anyhow::Result<String> {
+    let metadata = std::fs::metadata(exe)
+        .with_context(|| format!("Cannot read metadata of {}", exe.display()))?;
+    let modified = metadata
+        .modified()?
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    Ok(format!(
+        "{}-{:x}-{:x}",
+        env!("CARGO_PKG_VERSION"),
+        metadata.len(),
+        modified.as_nanos()
+    ))
+}
+
+impl Cache {
+    pub fn for_current_exe() -> anyhow::Result<Self> {
+        let exe = std::env::current_exe().context("Cannot locate the running binary")?;
+        let fingerprint = fingerprint(&exe)?;
+        let mut path = exe.into_os_string();
+        path.push(".answers.json");
+        Ok(Self::open(path.into(), fingerprint))
+    }
+
+    /// Opens the cache stored at `path`, keeping its answers only if they were produced by the
+    /// build identified by `fingerprint`. A missing or unreadable file gives an empty cache.
+    pub fn open(path: PathBuf, fingerprint: String) -> Self {
+        let answers = match std::fs::read_to_string(&path) {
+            Ok(s) => match serde_json::from_str::<Contents>(&s) {
+                Ok(contents) if contents.fingerprint == fingerprint => contents.answers,
+                Ok(_) => {
+                    tracing::debug!("Discarding answers cached by a previous build");
+                    BTreeMap::new()
+                }
+                Err(err) => {
+                    tracing::warn!("Ignoring corrupted cache {}: {}", path.display(), err);
+                    BTreeMap::new()
+                }
+            },
+            Err(err) if err.kind() == io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(err) => {
+                tracing::warn!("Cannot read cache {}: {}", path.display(), err);
+                BTreeMap::new()
+            }
+        };
+        Self {
+            path,
+            fingerprint,
+            answers: Mutex::new(answers),
+            modified: AtomicBool::new(false),
+        }
+    }
+
+    pub fn get(&self, key: &Key) -> Option<Answer> {
+        self.answers.lock().unwrap().get(&key.0).cloned()
+    }
+
+    pub fn insert(&self, key: Key, answer: Answer) {
+        self.answers.lock().unwrap().insert(key.0, answer);
+        self.modified.store(true, Ordering::Relaxed);
+    }
+
+    /// Writes the cache back if answers were added, through a temporary file so that concurrent
+    /// invocations never read a partial file
+    pub fn save(&self) -> anyhow::Result<()> {
+        if !self.modified.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+        let contents = Contents {
+            fingerprint: self.fingerprint.clone(),
+            answers: self.answers.lock().unwrap().clone(),
+        };
+        let mut tmp = self.path.clone().into_os_string();
+        tmp.push(format!(".{}", std::process::id()));
+        std::fs::write(&tmp, serde_json::to_vec_pretty(&contents)?)
+            .and_then(|_| std::fs::rename(&tmp, &self.path))
+            .with_context(|| format!("Cannot write cache {}", self.path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Cache, Key};
+    use crate::{testing::TempDir, Answer, Stage};
+
+    #[test]
+    fn test_key() {
+        let key = Key::new(3, Stage::Stage2, "00100\n11110\n");
+        assert_eq!(key, Key::new(3, Stage::Stage2, "00100\n11110\n"));
+        assert!(key.0.starts_with("day03/stage2/"));
+        assert_ne!(key, Key::new(3, Stage::Stage1, "00100\n11110\n"));
+        assert_ne!(key, Key::new(3, Stage::Stage2, "00100\n11111\n"));
+    }
+
+    #[test]
+    fn test_persistence() {
+        let dir = TempDir::new("cache");
+        let path = dir.join("day02.answers.json");
+
+        let key = Key::new(2, Stage::Stage1, "forward 5\n");
+        let cache = Cache::open(path.clone(), "build-1".into());
+        assert_eq!(None, cache.get(&key));
+        cache.insert(key.clone(), Answer::Integer(150));
+        cache.insert(
+            Key::new(2, Stage::Stage2, "forward 5\n"),
+            Answer::from("#.\n.#"),
+        );
+        let wide = Key::new(2, Stage::Stage1, "forward 9\n");
+        cache.insert(wide.clone(), Answer::Integer(i128::MIN));
+        cache.save().unwrap();
+
+        let cache = Cache::open(path.clone(), "build-1".into());
+        assert_eq!(Some(Answer::Integer(150)), cache.get(&key));
+        assert_eq!(
+            Some(Answer::from("#.\n.#")),
+            cache.get(&Key::new(2, Stage::Stage2, "forward 5\n"))
+        );
+        assert_eq!(Some(Answer::Integer(i128::MIN)), cache.get(&wide));
+
+        let rebuilt = Cache::open(path.clone(), "build-2".into());
+        assert_eq!(None, rebuilt.get(&key));
+
+        std::fs::write(&path, "{ not json").unwrap();
+        assert_eq!(None, Cache::open(path, "build-1".into()).get(&key));
+    }
+}
@@ -0,0 +1,470 @@
+//! Client of the puzzle site: downloads the puzzle inputs and submits answers.
+//!
+//! Requests are authenticated with the session token of a logged in user, taken from the
+//! `AOC_SESSION` environment variable by [`Client::from_env`]. Inputs are cached in the `inputs`
+//! directory, where [`Source::locate`](crate::Source::locate) looks for them, so that every
+//! input is downloaded only once.
+
+use std::{
+    fmt,
+    path::PathBuf,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::Context;
+
+use crate::{Answer, Stage};
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+pub const DEFAULT_YEAR: u16 = 2021;
+/// File of the cache directory holding the time before which answers cannot be submitted
+const NEXT_SUBMISSION: &str = ".next-submission";
+
+/// Hint given by the site on a wrong answer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hint {
+    TooHigh,
+    TooLow,
+}
+
+/// Verdict of the site on a submitted answer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Submission {
+    Correct,
+    Incorrect {
+        hint: Option<Hint>,
+    },
+    /// The stage was already solved, or is not unlocked yet
+    AlreadySolved,
+    /// An answer was submitted too recently, another one may be submitted after `wait`
+    RateLimited {
+        wait: Option<Duration>,
+    },
+}
+
+impl fmt::Display for Submission {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Correct => write!(f, "correct"),
+            Self::Incorrect { hint: None } => write!(f, "incorrect"),
+            Self::Incorrect {
+                hint: Some(Hint::TooHigh),
+            } => write!(f, "incorrect, too high"),
+            Self::Incorrect {
+                hint: Some(Hint::TooLow),
+            } => write!(f, "incorrect, too low"),
+            Self::AlreadySolved => write!(f, "already solved"),
+            Self::RateLimited { wait: None } => write!(f, "rate limited"),
+            Self::RateLimited { wait: Some(wait) } => {
+                write!(f, "rate limited, wait {}s", wait.as_secs())
+            }
+        }
+    }
+}
+
+impl Submission {
+    /// Reads the verdict out of the HTML page answering a submission
+    pub fn parse(page: &str) -> anyhow::Result<Self> {
+        let page = page.split_whitespace().collect::<Vec<_>>().join(" ");
+        Ok(if page.contains("That's the right answer") {
+            Self::Correct
+        } else if page.contains("That's not the right answer") {
+            let hint = if page.contains("too high") {
+                Some(Hint::TooHigh)
+            } else if page.contains("too low") {
+                Some(Hint::TooLow)
+            } else {
+                None
+            };
+            Self::Incorrect { hint }
+        } else if page.contains("You gave an answer too recently") {
+            Self::RateLimited {
+                wait: parse_wait(&page),
+            }
+        } else if page.contains("Did you already complete it") {
+            Self::AlreadySolved
+        } else {
+            anyhow::bail!("Cannot understand the answer of the site to the submission")
+        })
+    }
+}
+
+/// Parses the time left to wait in a page such as `You have 1m 5s left to wait.`
+fn parse_wait(page: &str) -> Option<Duration> {
+    let start = page.find("You have ")? + "You have ".len();
+    let end = start + page[start..].find(" left to wait")?;
+    let mut seconds = 0;
+    for part in page[start..end].split_whitespace() {
+        let (value, unit) = part.split_at(part.find(|c: char| !c.is_ascii_digit())?);
+        let value: u64 = value.parse().ok()?;
+        seconds += match unit {
+            "h" => value * 3600,
+            "m" => value * 60,
+            "s" => value,
+            _ => return None,
+        };
+    }
+    Some(Duration::from_secs(seconds))
+}
+
+/// Client of the puzzle site for a given year
+#[derive(Clone)]
+pub struct Client {
+    agent: ureq::Agent,
+    base_url: String,
+    year: u16,
+    session: String,
+    cache_dir: PathBuf,
+}
+
+impl fmt::Debug for Client {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Client")
+            .field("base_url", &self.base_url)
+            .field("year", &self.year)
+            .field("session", &"<redacted>")
+            .field("cache_dir", &self.cache_dir)
+            .finish()
+    }
+}
+
+impl Client {
+    pub fn new(session: impl Into<String>) -> Self {
+        Self {
+            agent: ureq::AgentBuilder::new()
+                .timeout(Duration::from_secs(30))
+                .user_agent(concat!(
+                    "github.com/1JLM7/advent-of-code-2021 platform/",
+                    env!("CARGO_PKG_VERSION")
+                ))
+                .build(),
+            base_url: DEFAULT_BASE_URL.into(),
+            year: DEFAULT_YEAR,
+            session: session.into(),
+            cache_dir: PathBuf::from("inputs"),
+        }
+    }
+
+    /// Creates a client with the session token of `AOC_SESSION`, and the base URL of
+    /// `AOC_BASE_URL` if set
+    pub fn from_env() -> anyhow::Result<Self> {
+        let session = std::env::var("AOC_SESSION")
+            .context("AOC_SESSION must be set to the session cookie of the puzzle site")?;
+        let client = Self::new(session.trim());
+        Ok(match std::env::var("AOC_BASE_URL") {
+            Ok(url) => client.with_base_url(url),
+            Err(_) => client,
+        })
+    }
+
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_owned();
+        self
+    }
+
+    pub fn with_year(mut self, year: u16) -> Self {
+        self.year = year;
+        self
+    }
+
+    pub fn with_cache_dir(mut self, cache_dir: impl Into<PathBuf>) -> Self {
+        self.cache_dir = cache_dir.into();
+        self
+    }
+
+    fn url(&self, day: u8, path: &str) -> String {
+        format!("{}/{}/day/{}{}", self.base_url, self.year, day, path)
+    }
+
+    fn cookie(&self) -> String {
+        format!("session={}", self.session)
+    }
+
+    /// Path of the cached input of `day`
+    pub fn cache_path(&self, day: u8) -> PathBuf {
+        self.cache_dir.join(format!("day{:02}.txt", day))
+    }
+
+    /// Returns the input of `day`, downloading it unless it is cached
+    pub fn input(&self, day: u8) -> anyhow::Result<String> {
+        let path = self.cache_path(day);
+        if path.is_file() {
+            tracing::debug!(path = %path.display(), "Input of day {} is cached", day);
+            return std::fs::read_to_string(&path)
+                .with_context(|| format!("Cannot read {}", path.display()));
+        }
+
+        let url = self.url(day, "/input");
+        tracing::info!(%url, "Downloading input of day {}", day);
+        let input = self
+            .agent
+            .get(&url)
+            .set("Cookie", &self.cookie())
+            .call()
+            .map_err(|err| request_error(err, &url))?
+            .into_string()?;
+        std::fs::create_dir_all(&self.cache_dir)
+            .and_then(|_| std::fs::write(&path, &input))
+            .with_context(|| format!("Cannot cache input in {}", path.display()))?;
+        Ok(input)
+    }
+
+    fn next_submission_path(&self) -> PathBuf {
+        self.cache_dir.join(NEXT_SUBMISSION)
+    }
+
+    /// Time left before the site accepts another answer, as told by the last submission
+    fn wait_left(&self) -> Option<Duration> {
+        let content = std::fs::read_to_string(self.next_submission_path()).ok()?;
+        let next = UNIX_EPOCH + Duration::from_secs(content.trim().parse().ok()?);
+        next.duration_since(SystemTime::now()).ok()
+    }
+
+    fn record_wait(&self, wait: Duration) -> anyhow::Result<()> {
+        let next = SystemTime::now().duration_since(UNIX_EPOCH)? + wait;
+        std::fs::create_dir_all(&self.cache_dir)?;
+        std::fs::write(self.next_submission_path(), next.as_secs().to_string())?;
+        Ok(())
+    }
+
+    /// Submits the answer to a stage. Nothing is sent while the site is known to rate limit
+    /// submissions.
+    pub fn submit(&self, day: u8, stage: Stage, answer: &Answer) -> anyhow::Result<Submission> {
+        if let Some(wait) = self.wait_left() {
+            return Ok(Submission::RateLimited { wait: Some(wait) });
+        }
+
+        let url = self.url(day, "/answer");
+        let level = match stage {
+            Stage::Stage1 => "1",
+            Stage::Stage2 => "2",
+            Stage::Custom(name) => anyhow::bail!("Custom stage {} cannot be submitted", name),
+        };
+        let answer = answer.to_string();
+        tracing::info!(%url, "Submitting {} for day {} {}", answer.trim(), day, stage);
+        let page = self
+            .agent
+            .post(&url)
+            .set("Cookie", &self.cookie())
+            .send_form(&[("level", level), ("answer", answer.trim())])
+            .map_err(|err| request_error(err, &url))?
+            .into_string()?;
+
+        let submission = Submission::parse(&page)?;
+        if let Submission::RateLimited { wait: Some(wait) } = submission {
+            self.record_wait(wait)?;
+        }
+        Ok(submission)
+    }
+}
+
+fn request_error(err: ureq::Error, url: &str) -> anyhow::Error {
+    match err {
+        ureq::Error::Status(code @ (400 | 401), _) => anyhow::anyhow!(
+            "Request to {} refused with status {}, is the session token valid?",
+            url,
+            code
+        ),
+        ureq::Error::Status(404, _) => {
+            anyhow::anyhow!("Nothing found at {}, is the puzzle unlocked yet?", url)
+        }
+        err => anyhow::Error::new(err).context(format!("Request to {} failed", url)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{Arc, Mutex},
+        thread::JoinHandle,
+        time::Duration,
+    };
+
+    use tiny_http::{Header, Response, Server};
+
+    use super::{parse_wait, Client, Hint, Submission};
+    use crate::{testing::TempDir, Answer, Stage};
+
+    /// Request received by the mock server: method, URL, cookie and body
+    type Request = (String, String, String, String);
+
+    /// Serves `responses`, one per request, recording the requests
+    struct MockServer {
+        url: String,
+        requests: Arc<Mutex<Vec<Request>>>,
+        thread: JoinHandle<()>,
+    }
+
+    impl MockServer {
+        fn start(responses: Vec<(u16, &'static str)>) -> Self {
+            let server = Server::http("127.0.0.1:0").unwrap();
+            let url = format!("http://{}", server.server_addr());
+            let requests = Arc::new(Mutex::new(Vec::new()));
+            let recorded = requests.clone();
+            let thread = std::thread::spawn(move || {
+                for (status, body) in responses {
+                    let mut request = server.recv().unwrap();
+                    let cookie = request
+                        .headers()
+                        .iter()
+                        .find(|h| h.field.equiv("Cookie"))
+                        .map(|h| h.value.to_string())
+                        .unwrap_or_default();
+                    let mut content = String::new();
+                    request.as_reader().read_to_string(&mut content).unwrap();
+                    recorded.lock().unwrap().push((
+                        request.method().to_string(),
+                        request.url().to_owned(),
+                        cookie,
+                        content,
+                    ));
+                    let header = Header::from_bytes("Content-Type", "text/html").unwrap();
+                    let response = Response::from_string(body)
+                        .with_status_code(status)
+                        .with_header(header);
+                    request.respond(response).unwrap();
+                }
+            });
+            Self {
+                url,
+                requests,
+                thread,
+            }
+        }
+
+        fn requests(self) -> Vec<Request> {
+            self.thread.join().unwrap();
+            Arc::try_unwrap(self.requests)
+                .unwrap()
+                .into_inner()
+                .unwrap()
+        }
+    }
+
+    fn client(server: &MockServer, cache: &TempDir) -> Client {
+        Client::new("c0ffee")
+            .with_base_url(format!("{}/", server.url))
+            .with_cache_dir(cache.to_path_buf())
+    }
+
+    #[test]
+    fn test_input_is_cached() {
+        let server = MockServer::start(vec![(200, "199\n200\n208\n")]);
+        let cache = TempDir::new("cache-input");
+        let client = client(&server, &cache);
+
+        assert_eq!("199\n200\n208\n", client.input(1).unwrap());
+        assert_eq!("199\n200\n208\n", client.input(1).unwrap());
+        assert!(client.cache_path(1).ends_with("day01.txt"));
+
+        let requests = server.requests();
+        assert_eq!(1, requests.len());
+        assert_eq!(
+            (
+                "GET".to_owned(),
+                "/2021/day/1/input".to_owned(),
+                "session=c0ffee".to_owned(),
+                String::new()
+            ),
+            requests[0]
+        );
+    }
+
+    #[test]
+    fn test_input_errors() {
+        let server = MockServer::start(vec![(404, "Not found"), (400, "Bad request")]);
+        let cache = TempDir::new("cache-errors");
+        let client = client(&server, &cache);
+
+        let err = client.input(25).unwrap_err().to_string();
+        assert!(err.contains("unlocked"), "{}", err);
+        let err = client.input(25).unwrap_err().to_string();
+        assert!(err.contains("session token"), "{}", err);
+        assert!(!client.cache_path(25).exists());
+        server.requests();
+    }
+
+    #[test]
+    fn test_submit() {
+        let server = MockServer::start(vec![
+            (
+                200,
+                "<article><p>That's not the right answer; your answer is too high.  If you're \
+                 stuck, make sure you're using the full input data</p></article>",
+            ),
+            (
+                200,
+                "<article><p>That's the right answer!  You are one gold star closer to saving \
+                 your vacation.</p></article>",
+            ),
+        ]);
+        let cache = TempDir::new("cache-submit");
+        let client = client(&server, &cache);
+
+        assert_eq!(
+            Submission::Incorrect {
+                hint: Some(Hint::TooHigh)
+            },
+            client.submit(2, Stage::Stage2, &Answer::from(900)).unwrap()
+        );
+        assert_eq!(
+            Submission::Correct,
+            client.submit(2, Stage::Stage2, &Answer::from(899)).unwrap()
+        );
+
+        let requests = server.requests();
+        assert_eq!("POST", requests[0].0);
+        assert_eq!("/2021/day/2/answer", requests[0].1);
+        assert_eq!("level=2&answer=900", requests[0].3);
+    }
+
+    #[test]
+    fn test_rate_limit() {
+        let server = MockServer::start(vec![(
+            200,
+            "<article><p>You gave an answer too recently; you have to wait after submitting an \
+             answer before trying again.  You have 1m 5s left to wait.</p></article>",
+        )]);
+        let cache = TempDir::new("cache-rate-limit");
+        let client = client(&server, &cache);
+
+        assert_eq!(
+            Submission::RateLimited {
+                wait: Some(Duration::from_secs(65))
+            },
+            client.submit(3, Stage::Stage1, &Answer::from(1)).unwrap()
+        );
+        // The site is not asked again until the wait is over
+        match client.submit(3, Stage::Stage1, &Answer::from(2)).unwrap() {
+            Submission::RateLimited { wait: Some(wait) } => {
+                assert!(wait <= Duration::from_secs(65))
+            }
+            submission => panic!("Unexpected submission {:?}", submission),
+        }
+        assert_eq!(1, server.requests().len());
+    }
+
+    #[test]
+    fn test_parse_submission() {
+        assert_eq!(
+            Submission::AlreadySolved,
+            Submission::parse(
+                "<p>You don't seem to be solving the right level.  Did you already complete \
+                 it?</p>"
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            Submission::Incorrect {
+                hint: Some(Hint::TooLow)
+            },
+            Submission::parse("That's not the right answer; your answer is too low.").unwrap()
+        );
+        assert!(Submission::parse("<html>Maintenance</html>").is_err());
+        assert_eq!(
+            Some(Duration::from_secs(34)),
+            parse_wait("You have 34s left to wait.")
+        );
+        assert_eq!(None, parse_wait("Please wait."));
+    }
+}
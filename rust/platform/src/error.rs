@@ -0,0 +1,154 @@
+use std::{fmt, process::ExitCode, time::Duration};
+
+use crate::parse::ParseError;
+
+/// Failure while running a challenge, telling apart the phase which failed
+#[derive(Debug)]
+pub enum Error {
+    /// The puzzle input could not be parsed
+    Parse(anyhow::Error),
+    /// A stage failed while solving the parsed input
+    Solve(anyhow::Error),
+    /// The challenge called `todo!()` or `unimplemented!()`
+    Unimplemented,
+    /// The phase was given up after running for the given time, longer than `--timeout`
+    TimedOut(Duration),
+    /// The phase was cancelled with Ctrl-C
+    Cancelled,
+}
+
+impl Error {
+    fn inner(&self) -> Option<&anyhow::Error> {
+        match self {
+            Self::Parse(err) | Self::Solve(err) => Some(err),
+            Self::Unimplemented | Self::TimedOut(_) | Self::Cancelled => None,
+        }
+    }
+
+    /// Part of the input `data` at which parsing failed, when the error locates it
+    pub(crate) fn snippet(&self, data: &str) -> Option<String> {
+        match self {
+            Self::Parse(err) => ParseError::find(err)?.snippet(data),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse(_) => write!(f, "cannot parse input")?,
+            Self::Solve(_) => write!(f, "stage failed")?,
+            Self::Unimplemented => write!(f, "not implemented")?,
+            Self::TimedOut(elapsed) => write!(f, "timed out after {:.3?}", elapsed)?,
+            Self::Cancelled => write!(f, "cancelled")?,
+        }
+        if let Some(inner) = self.inner().filter(|_| f.alternate()) {
+            write!(f, ": {:#}", inner)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.inner().map(|err| err.as_ref())
+    }
+}
+
+/// Process exit status of the runners, from the least to the most severe
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ExitStatus {
+    Success,
+    /// Some stages are not implemented yet, every other stage succeeded
+    Unimplemented,
+    /// Some answers did not match the expected ones
+    Mismatch,
+    /// A stage or the platform itself failed
+    Failure,
+}
+
+impl ExitStatus {
+    pub fn code(self) -> u8 {
+        match self {
+            Self::Success => 0,
+            Self::Failure => 1,
+            Self::Mismatch => 2,
+            Self::Unimplemented => 3,
+        }
+    }
+
+    /// Exit code of a runner, printing the error which aborted it if any
+    pub(crate) fn report(status: anyhow::Result<ExitStatus>) -> ExitCode {
+        match status {
+            Ok(status) => status.into(),
+            Err(err) => {
+                eprintln!("Error: {:?}", err);
+                Self::Failure.into()
+            }
+        }
+    }
+}
+
+impl From<ExitStatus> for ExitCode {
+    fn from(status: ExitStatus) -> Self {
+        ExitCode::from(status.code())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Error, ExitStatus};
+    use crate::parse::{self, Text};
+
+    #[test]
+    fn test_display() {
+        let err = Error::Parse(anyhow::anyhow!("Syntax error"));
+
+        assert_eq!("cannot parse input", err.to_string());
+        assert_eq!("cannot parse input: Syntax error", format!("{:#}", err));
+        assert_eq!(
+            "stage failed: No players could win the game !",
+            format!(
+                "{:#}",
+                anyhow::Error::new(Error::Solve(anyhow::anyhow!(
+                    "No players could win the game !"
+                )))
+            )
+        );
+        assert_eq!("not implemented", format!("{:#}", Error::Unimplemented));
+        assert_eq!(
+            "timed out after 2.500s",
+            Error::TimedOut(std::time::Duration::from_millis(2500)).to_string()
+        );
+    }
+
+    #[test]
+    fn test_snippet() {
+        let data = "7,4,9\n\n22 13\n 8\n";
+        let board = Text::new(data).blocks().nth(1).unwrap();
+        let err = parse::number_grid::<u32>(board).unwrap_err();
+        let err = Error::Parse(anyhow::Error::new(err).context("Parsing board 1"));
+        assert_eq!(Some("  |\n4 |  8\n  |  ^".to_owned()), err.snippet(data));
+        assert_eq!(
+            None,
+            Error::Parse(anyhow::anyhow!("Syntax error")).snippet(data)
+        );
+        assert_eq!(None, Error::Unimplemented.snippet(data));
+    }
+
+    #[test]
+    fn test_exit_status_severity() {
+        assert_eq!(
+            Some(ExitStatus::Mismatch),
+            [
+                ExitStatus::Unimplemented,
+                ExitStatus::Mismatch,
+                ExitStatus::Success
+            ]
+            .into_iter()
+            .max()
+        );
+        assert_eq!(3, ExitStatus::Unimplemented.code());
+    }
+}
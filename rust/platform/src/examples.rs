@@ -0,0 +1,170 @@
+//! Test harness generating one test per example input and per stage of a challenge.
+//!
+//! The examples of a day live in its `examples/` directory: every `NAME.txt` file is an input,
+//! and `answers.toml` holds the expected answers, keyed by example name then stage:
+//!
+//! ```toml
+//! [sample]
+//! stage1 = 150
+//! stage2 = 900
+//! ```
+//!
+//! Variants of a stage are checked against the answer of the stage unless they have their own.
+//! Stages without an expected answer are reported as ignored. The harness is set up in the day
+//! crate with a `tests/examples.rs` file containing `platform::examples!(DayNN);`, registered in
+//! its manifest with `harness = false`.
+
+use std::{path::Path, sync::Arc};
+
+use anyhow::Context;
+use libtest_mimic::{Arguments, Failed, Trial};
+
+use crate::{
+    exec::guard, registry::Entry, verify::Answers, Answer, Challenge, Error, ExitStatus, Stage,
+};
+
+/// Name of the manifest of expected answers, in the examples directory
+pub const ANSWERS: &str = "answers.toml";
+
+/// Solves a stage of an example, failing unless it gives the expected answer
+fn check(challenge: &dyn Entry, data: &str, stage: Stage, expected: &Answer) -> Result<(), Failed> {
+    let input =
+        guard(|| challenge.parse(data), Error::Parse).map_err(|err| match err.snippet(data) {
+            Some(snippet) => Failed::from(format!("{:#}\n{}", err, snippet)),
+            None => Failed::from(format!("{:#}", err)),
+        })?;
+    let answer = guard(|| challenge.stage(stage, &input), Error::Solve)
+        .map_err(|err| Failed::from(format!("{:#}", err)))?;
+    if &answer != expected {
+        return Err(format!("answered {}, expected {}", answer, expected).into());
+    }
+    Ok(())
+}
+
+/// Lists the examples of `dir`, sorted by name, along with their expected answers
+fn trials(challenge: Arc<dyn Entry>, dir: &Path) -> anyhow::Result<Vec<Trial>> {
+    let answers = Answers::load(dir.join(ANSWERS))?;
+    let mut examples = Vec::new();
+    for entry in std::fs::read_dir(dir).with_context(|| format!("Listing {}", dir.display()))? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "txt") {
+            examples.push(path);
+        }
+    }
+    examples.sort();
+
+    let mut trials = Vec::new();
+    for path in examples {
+        let name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let data = Arc::new(
+            std::fs::read_to_string(&path)
+                .with_context(|| format!("Reading example {}", path.display()))?,
+        );
+        for stage in challenge.stages() {
+            // Variants are expected to give the answer of their official stage
+            let expected = answers
+                .get(&name, stage)
+                .or_else(|| answers.get(&name, challenge.official(stage)))
+                .cloned();
+            let ignored = expected.is_none();
+            let (challenge, data) = (challenge.clone(), data.clone());
+            let trial = Trial::test(format!("{}::{}", name, stage), move || {
+                let expected =
+                    expected.ok_or_else(|| format!("no expected answer in {}", ANSWERS))?;
+                check(&*challenge, &data, stage, &expected)
+            });
+            trials.push(trial.with_ignored_flag(ignored));
+        }
+    }
+    Ok(trials)
+}
+
+/// Runs the examples of `dir` against `challenge`, with the command line of the test harness.
+/// Used through the [`examples!`](crate::examples!) macro.
+pub fn run<C: 'static + std::fmt::Debug + Challenge + Send + Sync>(
+    challenge: C,
+    dir: &Path,
+) -> std::process::ExitCode {
+    let args = Arguments::from_args();
+    match trials(Arc::new(challenge), dir) {
+        Ok(trials) => libtest_mimic::run(&args, trials).exit_code(),
+        Err(err) => ExitStatus::report(Err(err)),
+    }
+}
+
+/// Declares the `main` function of a test target running the examples of the day crate
+#[macro_export]
+macro_rules! examples {
+    ($c: expr) => {
+        fn main() -> ::std::process::ExitCode {
+            $crate::examples::run(
+                $c,
+                ::std::path::Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/examples")),
+            )
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::{check, trials};
+    use crate::{
+        testing::{Sum, TempDir},
+        Answer, Stage,
+    };
+
+    fn examples_dir() -> TempDir {
+        let dir = TempDir::new("examples");
+        std::fs::write(dir.join("small.txt"), "1 2 3").unwrap();
+        std::fs::write(dir.join("large.txt"), "100 200").unwrap();
+        std::fs::write(dir.join("notes.md"), "Not an example").unwrap();
+        std::fs::write(
+            dir.join("answers.toml"),
+            "[small]\nstage1 = 6\n\n[large]\nstage1 = 300\n",
+        )
+        .unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_trials() {
+        let trials = trials(Arc::new(Sum), &examples_dir()).unwrap();
+        let names: Vec<_> = trials
+            .iter()
+            .map(|t| (t.name(), t.has_ignored_flag()))
+            .collect();
+        assert_eq!(
+            vec![
+                ("large::stage1", false),
+                ("large::stage2", true),
+                ("large::stage1-capped", false),
+                ("small::stage1", false),
+                ("small::stage2", true),
+                ("small::stage1-capped", false),
+            ],
+            names
+        );
+    }
+
+    #[test]
+    fn test_check() {
+        assert!(check(&Sum, "1 2 3", Stage::Stage1, &Answer::Integer(6)).is_ok());
+
+        let failed = check(&Sum, "1 2 3", Stage::Stage1, &Answer::Integer(7)).unwrap_err();
+        assert_eq!(Some("answered 6, expected 7"), failed.message());
+
+        let failed = check(&Sum, "1 two", Stage::Stage1, &Answer::Integer(1)).unwrap_err();
+        assert!(failed
+            .message()
+            .unwrap()
+            .starts_with("cannot parse input: "));
+
+        let failed = check(&Sum, "1 2 3", Stage::Stage2, &Answer::Integer(1)).unwrap_err();
+        assert_eq!(Some("not implemented"), failed.message());
+    }
+}
@@ -0,0 +1,635 @@
+//! Execution of the phases of a challenge on the blocking thread pool of the runtime.
+
+use std::{
+    collections::HashMap,
+    num::NonZeroUsize,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use structopt::StructOpt;
+use tokio::{
+    runtime::{Builder, Runtime},
+    sync::{watch, Semaphore},
+};
+use tracing::Instrument;
+
+use crate::{
+    bench,
+    cache::{Cache, Key},
+    panic,
+    registry::{Entry, Input},
+    report::{Summary, Tally},
+    verify::{Answers, Verdict},
+    Answer, Error, Source, Stage,
+};
+
+#[derive(Debug, StructOpt)]
+pub(crate) struct RuntimeOptions {
+    /// Number of threads solving challenges, the number of CPUs by default. With a single
+    /// thread, stages run one after the other
+    #[structopt(long, name = "threads")]
+    pub threads: Option<NonZeroUsize>,
+    /// Give up on stages running longer than this, such as `30s`, `500ms` or `2m`. Seconds are
+    /// assumed without unit. A stage given up on keeps its thread busy until it returns. With
+    /// `--bench`, the whole benchmark is given this much time per iteration
+    #[structopt(long, name = "duration", parse(try_from_str = parse_duration))]
+    pub timeout: Option<Duration>,
+}
+
+fn parse_duration(s: &str) -> anyhow::Result<Duration> {
+    let s = s.trim();
+    let (value, unit) = match s.find(|c: char| c.is_ascii_alphabetic()) {
+        Some(i) => s.split_at(i),
+        None => (s, "s"),
+    };
+    let value: f64 = value
+        .parse()
+        .map_err(|_| anyhow::anyhow!("Cannot parse duration from {:?}", s))?;
+    let seconds = match unit {
+        "ms" => value / 1000.,
+        "s" => value,
+        "m" => value * 60.,
+        _ => anyhow::bail!(
+            "Unknown unit {:?} in duration {:?}, expected ms, s or m",
+            unit,
+            s
+        ),
+    };
+    Ok(Duration::try_from_secs_f64(seconds)?)
+}
+
+impl RuntimeOptions {
+    pub fn threads(&self) -> NonZeroUsize {
+        self.threads
+            .or_else(|| std::thread::available_parallelism().ok())
+            .unwrap_or(NonZeroUsize::MIN)
+    }
+
+    /// Limits of the phases run with these options, see [`Limits::new`]
+    pub fn limits(&self) -> Limits {
+        Limits::new(self.threads(), self.timeout)
+    }
+
+    /// Builds the runtime, whose blocking threads solve the challenges
+    pub fn runtime(&self) -> anyhow::Result<Runtime> {
+        let threads = self.threads();
+        let mut builder = if threads.get() == 1 {
+            Builder::new_current_thread()
+        } else {
+            let mut builder = Builder::new_multi_thread();
+            builder.worker_threads(threads.get());
+            builder
+        };
+        Ok(builder
+            .enable_all()
+            .thread_name("aoc-platform-thread")
+            .build()?)
+    }
+}
+
+/// Runs `f`, measuring how long it took to succeed
+fn timed<T>(f: impl FnOnce() -> anyhow::Result<T>) -> anyhow::Result<(T, Duration)> {
+    let start = Instant::now();
+    let value = f()?;
+    Ok((value, start.elapsed()))
+}
+
+/// Runs a phase of a challenge, turning its errors and panics into an [`Error`] built by `wrap`.
+/// Panics raised by `todo!()` or `unimplemented!()` become [`Error::Unimplemented`].
+pub(crate) fn guard<T>(
+    f: impl FnOnce() -> anyhow::Result<T>,
+    wrap: fn(anyhow::Error) -> Error,
+) -> Result<T, Error> {
+    match panic::catch(f) {
+        Ok(res) => res.map_err(wrap),
+        Err(panic) if panic.is_unimplemented() => Err(Error::Unimplemented),
+        Err(panic) => Err(wrap(panic.into())),
+    }
+}
+
+/// Bounds on the phases of a challenge: how many run at once, for how long, and the
+/// cancellation of every phase on Ctrl-C
+#[derive(Debug, Clone)]
+pub(crate) struct Limits {
+    slots: Arc<Semaphore>,
+    threads: NonZeroUsize,
+    timeout: Option<Duration>,
+    cancelled: watch::Receiver<bool>,
+}
+
+impl Limits {
+    /// Creates the limits and starts listening for Ctrl-C, which must be done from within the
+    /// runtime. A second Ctrl-C exits right away.
+    pub fn new(threads: NonZeroUsize, timeout: Option<Duration>) -> Self {
+        let (cancel, cancelled) = watch::channel(false);
+        tokio::spawn(async move {
+            if tokio::signal::ctrl_c().await.is_ok() {
+                eprintln!("Interrupted, cancelling the running stages");
+                cancel.send_replace(true);
+                if tokio::signal::ctrl_c().await.is_ok() {
+                    std::process::exit(130);
+                }
+            }
+        });
+        Self {
+            slots: Arc::new(Semaphore::new(threads.get())),
+            threads,
+            timeout,
+            cancelled,
+        }
+    }
+
+    async fn cancelled(&self) {
+        let mut cancelled = self.cancelled.clone();
+        if cancelled.wait_for(|c| *c).await.is_err() {
+            std::future::pending::<()>().await;
+        }
+    }
+
+    /// Limits of a phase repeating `times` a phase bounded by these limits, such as a benchmark.
+    /// The timeout becomes a budget for the whole phase, `times` the original one: a single
+    /// repetition may run longer as long as the others make up for it.
+    fn repeated(&self, times: NonZeroUsize) -> Self {
+        let times = u32::try_from(times.get()).unwrap_or(u32::MAX);
+        Self {
+            timeout: self.timeout.and_then(|timeout| timeout.checked_mul(times)),
+            ..self.clone()
+        }
+    }
+
+    /// Runs a phase on the blocking thread pool once a slot is free, giving up on timeout or
+    /// cancellation. The phase itself cannot be interrupted: its thread is abandoned, and keeps
+    /// its slot until the phase returns so that no more phases than slots ever run at once.
+    async fn run<T: Send + 'static, E: From<Error> + Send + 'static>(
+        &self,
+        phase: impl FnOnce() -> Result<T, E> + Send + 'static,
+        wrap: fn(anyhow::Error) -> E,
+    ) -> Result<T, E> {
+        let slot = tokio::select! {
+            slot = self.slots.clone().acquire_owned() => {
+                slot.expect("the semaphore is never closed")
+            }
+            _ = self.cancelled() => return Err(Error::Cancelled.into()),
+        };
+        let start = Instant::now();
+        let timeout = async {
+            match self.timeout {
+                Some(timeout) => tokio::time::sleep(timeout).await,
+                None => std::future::pending().await,
+            }
+        };
+        // The phase runs on a blocking thread, where the span of the caller must be entered again
+        let span = tracing::Span::current();
+        let task = tokio::task::spawn_blocking(move || {
+            let _slot = slot;
+            let _span = span.enter();
+            phase()
+        });
+        let abandoned = tokio::select! {
+            res = task => return res.unwrap_or_else(|err| Err(wrap(err.into()))),
+            _ = timeout => Error::TimedOut(start.elapsed()),
+            _ = self.cancelled() => Error::Cancelled,
+        };
+        tracing::warn!(
+            "Abandoned phase keeps running until it returns, leaving {} of {} threads to the \
+             other phases",
+            self.slots.available_permits(),
+            self.threads
+        );
+        Err(abandoned.into())
+    }
+}
+
+async fn parse_input(
+    challenge: Arc<dyn Entry>,
+    data: Arc<str>,
+    limits: &Limits,
+) -> Result<(Input, Duration), Error> {
+    let span = tracing::trace_span!("parse-input", day = challenge.day());
+    limits
+        .run(
+            move || guard(|| timed(|| challenge.parse(&data)), Error::Parse),
+            Error::Parse,
+        )
+        .instrument(span)
+        .await
+}
+
+async fn exec_stage(
+    challenge: Arc<dyn Entry>,
+    stage: Stage,
+    input: Input,
+    limits: Limits,
+) -> Result<(Answer, Duration), Error> {
+    let span = tracing::trace_span!("exec-challenge", day = challenge.day(), %stage);
+    limits
+        .run(
+            move || guard(|| timed(|| challenge.stage(stage, &input)), Error::Solve),
+            Error::Solve,
+        )
+        .instrument(span)
+        .await
+}
+
+/// Benchmarks a stage within `limits`, whose timeout is given to each iteration, reading and
+/// parsing the input included, and enforced on the benchmark as a whole
+pub(crate) async fn exec_bench(
+    challenge: Arc<dyn Entry>,
+    stage: Stage,
+    source: Source,
+    iterations: NonZeroUsize,
+    limits: &Limits,
+) -> anyhow::Result<bench::Report> {
+    limits
+        .repeated(iterations)
+        .run(
+            move || bench::bench(&*challenge, stage, &source, iterations),
+            |err| err,
+        )
+        .await
+}
+
+/// Answer of a stage and the time it took to solve it, `None` when the answer came from the cache
+pub(crate) type StageResult = Result<(Answer, Option<Duration>), Error>;
+
+/// Outcome of running the stages of a challenge. It is reported once every stage finished, so
+/// that challenges running concurrently are reported in a deterministic order.
+#[derive(Debug)]
+pub(crate) enum Outcome {
+    /// The input could not be obtained, `phase` telling whether reading or parsing it failed.
+    /// `snippet` shows the part of the input at which parsing failed, when the error locates it.
+    Failed {
+        phase: &'static str,
+        error: anyhow::Error,
+        snippet: Option<String>,
+    },
+    /// No input was found for the challenge, which is skipped when running every day
+    Missing { error: anyhow::Error },
+    /// `parse` is `None` when the answer of every stage came from the cache, the input being
+    /// left unparsed
+    Solved {
+        parse: Option<Duration>,
+        stages: Vec<(Stage, StageResult)>,
+    },
+}
+
+impl Outcome {
+    /// Prints the answers and errors, recording every phase in `summary` with labels starting
+    /// with `prefix`. Variants are checked against their official stage when both were run,
+    /// counting as failed when they disagree.
+    pub fn report(
+        self,
+        challenge: &dyn Entry,
+        prefix: &str,
+        num_stages: usize,
+        answers: Option<&Answers>,
+        summary: &mut Summary,
+    ) -> Tally {
+        let day = challenge.day();
+        let mut tally = Tally::default();
+        let stages = match self {
+            Self::Failed {
+                phase,
+                error,
+                snippet,
+            } => {
+                eprintln!("{}{}: {:#}", prefix, phase, error);
+                if let Some(snippet) = snippet {
+                    eprintln!("{}", snippet);
+                }
+                summary.push(format!("{}{}", prefix, phase), None, "error");
+                tally.failed += num_stages;
+                return tally;
+            }
+            Self::Missing { error } => {
+                eprintln!("{}skipped: {:#}", prefix, error);
+                summary.push(format!("{}input", prefix), None, "missing");
+                return tally;
+            }
+            Self::Solved { parse, stages } => {
+                let status = if parse.is_some() { "ok" } else { "cached" };
+                summary.push(format!("{}parse", prefix), parse, status);
+                stages
+            }
+        };
+
+        let solved: HashMap<_, _> = stages
+            .iter()
+            .filter_map(|(stage, result)| Some((*stage, result.as_ref().ok()?.0.clone())))
+            .collect();
+        for (stage, result) in stages {
+            let label = format!("{}{}", prefix, stage);
+            let official = challenge.official(stage);
+            match result {
+                Ok((answer, elapsed)) => {
+                    let verdict = answers.map(|a| a.check(day, official, &answer));
+                    crate::report::print_answer(&label, &answer, verdict.as_ref());
+                    if verdict.as_ref().is_some_and(|v| v.is_failure()) {
+                        tally.mismatched += 1;
+                    }
+                    let checked = verdict.as_ref().is_some_and(|v| *v != Verdict::Missing);
+                    let mut status = verdict.map_or_else(|| "ok".to_owned(), |v| v.to_string());
+                    match solved.get(&official) {
+                        Some(expected) if official != stage && expected != &answer => {
+                            eprintln!(
+                                "{}: differs from {}, which answered {}",
+                                label, official, expected
+                            );
+                            status = "differs".to_owned();
+                            tally.failed += 1;
+                        }
+                        Some(_) if official != stage && !checked => {
+                            status = "agrees".to_owned();
+                        }
+                        _ => {}
+                    }
+                    match elapsed {
+                        Some(_) => summary.push(label, elapsed, status),
+                        None => summary.push(label, None, format!("{} (cached)", status)),
+                    }
+                }
+                Err(Error::Unimplemented) => {
+                    eprintln!("{}: not implemented, skipped", label);
+                    summary.push(label, None, "not implemented");
+                    tally.unimplemented += 1;
+                }
+                Err(Error::TimedOut(elapsed)) => {
+                    eprintln!("{}: {}", label, Error::TimedOut(elapsed));
+                    summary.push(label, Some(elapsed), "timed out");
+                    tally.failed += 1;
+                }
+                Err(Error::Cancelled) => {
+                    eprintln!("{}: cancelled", label);
+                    summary.push(label, None, "cancelled");
+                    tally.failed += 1;
+                }
+                Err(err) => {
+                    eprintln!("{}: {:#}", label, err);
+                    summary.push(label, None, "error");
+                    tally.failed += 1;
+                }
+            }
+        }
+        tally
+    }
+}
+
+/// Reads and parses the data file once, then runs the stages concurrently over the parsed input.
+/// Stages with an answer in `cache` are not run, and the input is not even parsed when every
+/// stage has one; the answers of the stages which did run are added to it.
+pub(crate) async fn run_stages(
+    challenge: Arc<dyn Entry>,
+    source: Source,
+    stages: &[Stage],
+    limits: &Limits,
+    cache: Option<&Cache>,
+) -> Outcome {
+    let data = match source.read().await {
+        Ok(data) => data,
+        Err(error) => {
+            return Outcome::Failed {
+                phase: "read",
+                error,
+                snippet: None,
+            }
+        }
+    };
+    let keys: Vec<_> = stages
+        .iter()
+        .map(|&stage| Key::new(challenge.day(), stage, &data))
+        .collect();
+    let cached: Vec<_> = keys
+        .iter()
+        .map(|key| cache.and_then(|cache| cache.get(key)))
+        .collect();
+    if cached.iter().all(Option::is_some) {
+        tracing::debug!("Every stage answer is cached, skipping parsing");
+        return Outcome::Solved {
+            parse: None,
+            stages: stages
+                .iter()
+                .zip(cached)
+                .map(|(&stage, answer)| (stage, Ok((answer.unwrap(), None))))
+                .collect(),
+        };
+    }
+
+    let data: Arc<str> = data.into();
+    let (input, parse) = match parse_input(challenge.clone(), data.clone(), limits).await {
+        Ok(parsed) => parsed,
+        Err(err) => {
+            return Outcome::Failed {
+                phase: "parse",
+                snippet: err.snippet(&data),
+                error: err.into(),
+            }
+        }
+    };
+
+    let tasks: Vec<_> = stages
+        .iter()
+        .zip(cached)
+        .map(|(&stage, answer)| {
+            let task = match answer {
+                Some(_) => None,
+                None => Some(tokio::spawn(exec_stage(
+                    challenge.clone(),
+                    stage,
+                    input.clone(),
+                    limits.clone(),
+                ))),
+            };
+            (stage, answer, task)
+        })
+        .collect();
+    let mut results = Vec::with_capacity(tasks.len());
+    for ((stage, answer, task), key) in tasks.into_iter().zip(keys) {
+        let result = match (answer, task) {
+            (Some(answer), _) => Ok((answer, None)),
+            (None, Some(task)) => task
+                .await
+                .unwrap_or_else(|err| Err(Error::Solve(err.into())))
+                .map(|(answer, elapsed)| (answer, Some(elapsed))),
+            (None, None) => unreachable!("stage neither cached nor run"),
+        };
+        if let (Some(cache), Ok((answer, Some(_)))) = (cache, &result) {
+            cache.insert(key, answer.clone());
+        }
+        results.push((stage, result));
+    }
+    Outcome::Solved {
+        parse: Some(parse),
+        stages: results,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        num::NonZeroUsize,
+        sync::Arc,
+        time::{Duration, Instant},
+    };
+
+    use tokio::sync::watch;
+
+    use super::{exec_bench, guard, parse_duration, Limits, Outcome};
+    use crate::{
+        report::Summary,
+        testing::{Fake, Slow, TempDir},
+        Answer, Error, ExitStatus, Source, Stage,
+    };
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(Duration::from_secs(30), parse_duration("30").unwrap());
+        assert_eq!(Duration::from_millis(1500), parse_duration("1.5s").unwrap());
+        assert_eq!(Duration::from_millis(250), parse_duration("250ms").unwrap());
+        assert_eq!(Duration::from_secs(120), parse_duration("2m").unwrap());
+        assert!(parse_duration("2h").is_err());
+        assert!(parse_duration("-1s").is_err());
+        assert!(parse_duration("soon").is_err());
+    }
+
+    fn runtime() -> tokio::runtime::Runtime {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_timeout() {
+        let rt = runtime();
+        let (slow, waited) = rt.block_on(async {
+            let limits = Limits::new(NonZeroUsize::MIN, Some(Duration::from_millis(20)));
+            let slow = limits.run(
+                || {
+                    std::thread::sleep(Duration::from_millis(300));
+                    Ok(((), Duration::ZERO))
+                },
+                Error::Solve,
+            );
+            let start = Instant::now();
+            let fast = async {
+                let res = limits.run(|| Ok((1, Duration::ZERO)), Error::Solve).await;
+                (res, start.elapsed())
+            };
+            let (slow, (fast, waited)) = tokio::join!(slow, fast);
+            assert_eq!(1, fast.unwrap().0);
+            (slow, waited)
+        });
+        rt.shutdown_background();
+        match slow {
+            Err(Error::TimedOut(elapsed)) => assert!(elapsed >= Duration::from_millis(20)),
+            res => panic!("Expected a timeout, got {:?}", res),
+        }
+        // The single slot is only freed once the abandoned phase returns
+        assert!(waited >= Duration::from_millis(300));
+    }
+
+    #[test]
+    fn test_bench_limits() {
+        let dir = TempDir::new("bench");
+        let source = Source::File(dir.join("day06.txt"));
+        std::fs::write(dir.join("day06.txt"), "").unwrap();
+        let iterations = NonZeroUsize::new(3).unwrap();
+
+        let rt = runtime();
+        let (timed_out, passed, cancelled) = rt.block_on(async {
+            let limits = Limits::new(NonZeroUsize::MIN, Some(Duration::from_millis(20)));
+            let timed_out = exec_bench(
+                Arc::new(Slow),
+                Stage::Stage1,
+                source.clone(),
+                iterations,
+                &limits,
+            );
+            // The benchmark is given the timeout once per iteration, 600ms for 150ms of work
+            let limits = Limits::new(NonZeroUsize::MIN, Some(Duration::from_millis(200)));
+            let passed = exec_bench(
+                Arc::new(Slow),
+                Stage::Stage1,
+                source.clone(),
+                iterations,
+                &limits,
+            );
+            let limits = Limits {
+                cancelled: watch::channel(true).1,
+                ..limits.clone()
+            };
+            let cancelled = exec_bench(Arc::new(Slow), Stage::Stage1, source, iterations, &limits);
+            (timed_out.await, passed.await, cancelled.await)
+        });
+        rt.shutdown_background();
+
+        assert!(matches!(
+            timed_out.unwrap_err().downcast_ref(),
+            Some(Error::TimedOut(_))
+        ));
+        assert_eq!(Answer::Integer(6), passed.unwrap().answer);
+        assert!(matches!(
+            cancelled.unwrap_err().downcast_ref(),
+            Some(Error::Cancelled)
+        ));
+    }
+
+    #[test]
+    fn test_guard() {
+        assert_eq!(2, guard(|| Ok(2), Error::Solve).unwrap());
+        assert!(matches!(
+            guard(|| -> anyhow::Result<()> { todo!() }, Error::Solve),
+            Err(Error::Unimplemented)
+        ));
+        assert!(matches!(
+            guard(
+                || -> anyhow::Result<()> { anyhow::bail!("oops") },
+                Error::Parse
+            ),
+            Err(Error::Parse(_))
+        ));
+
+        let err = guard(|| -> anyhow::Result<u32> { Ok(vec![1][2]) }, Error::Solve).unwrap_err();
+        assert!(matches!(err, Error::Solve(_)));
+        assert!(format!("{:#}", err).contains("index out of bounds"));
+    }
+
+    #[test]
+    fn test_cross_check() {
+        let solved = |answer: i32| Ok((Answer::from(answer), Some(Duration::from_millis(1))));
+        let cross_check = |variant: i32| {
+            let outcome = Outcome::Solved {
+                parse: Some(Duration::ZERO),
+                stages: vec![
+                    (Stage::Stage1, solved(5)),
+                    (Stage::Custom("stage1-naive"), solved(variant)),
+                ],
+            };
+            let mut summary = Summary::default();
+            let tally = outcome.report(&Fake::<6>, "", 2, None, &mut summary);
+            (tally.failed, summary.to_string())
+        };
+
+        let (failed, summary) = cross_check(5);
+        assert_eq!(0, failed);
+        assert!(summary.contains("stage1-naive     1.000ms  agrees\n"));
+        let (failed, summary) = cross_check(6);
+        assert_eq!(1, failed);
+        assert!(summary.contains("stage1-naive     1.000ms  differs\n"));
+    }
+
+    #[test]
+    fn test_missing_input() {
+        let outcome = Outcome::Missing {
+            error: anyhow::anyhow!("No input found for day 6"),
+        };
+        let mut summary = Summary::default();
+        let tally = outcome.report(&Fake::<6>, "day06 ", 2, None, &mut summary);
+
+        assert_eq!(ExitStatus::Success, tally.status());
+        assert!(summary
+            .to_string()
+            .starts_with("Summary:\n  day06 input           -  missing\n"));
+    }
+}
@@ -0,0 +1,343 @@
+//! Differential testing of challenges over randomly generated inputs.
+//!
+//! A day implementing [`Generate`] produces valid data files from a seeded random generator.
+//! [`check`] runs every generated input through each official stage, its
+//! [variants](crate::CustomStage::variant) and the [reference](Generate::reference) answer, and
+//! reports the first input on which they disagree or fail, shrunk to a smaller input failing
+//! the same way. The day crate runs it from a unit test declared by the
+//! [`generated!`](crate::generated!) macro, next to its implementation of [`Generate`]:
+//!
+//! ```ignore
+//! platform::generated!(Day02);
+//! ```
+//!
+//! The seed, the number of cases and the maximum size of the inputs can be changed with the
+//! `AOC_SEED`, `AOC_CASES` and `AOC_SIZE` environment variables.
+
+use std::{fmt, str::FromStr};
+
+use rand::SeedableRng;
+
+use crate::{exec::guard, registry::Entry, Answer, Challenge, Error, Stage};
+
+/// Random generator handed to [`Generate::generate`], reproducible from its seed
+pub type Rng = rand::rngs::StdRng;
+
+/// Challenges able to generate their own inputs
+pub trait Generate: Challenge {
+    /// Builds a random valid data file made of about `size` elements, such as lines, boards or
+    /// segments
+    fn generate(&self, rng: &mut Rng, size: usize) -> String;
+
+    /// Answer of `stage` computed independently of the challenge, usually by a naive solver.
+    /// `None` when there is no reference for that stage, or when the puzzle leaves the answer
+    /// undefined for `data`.
+    fn reference(&self, _data: &str, _stage: Stage) -> Option<Answer> {
+        None
+    }
+
+    /// Smaller inputs derived from a failing `data`, tried in order while shrinking. Chunks of
+    /// lines are removed by default, candidates which are not valid inputs being discarded
+    /// since they fail in another way.
+    fn shrink(&self, data: &str) -> Vec<String> {
+        shrink_lines(data)
+    }
+}
+
+/// Inputs made of `data` without one chunk of its lines, from the largest chunks to single lines
+pub fn shrink_lines(data: &str) -> Vec<String> {
+    let lines: Vec<_> = data.lines().collect();
+    let mut candidates = Vec::new();
+    let mut chunk = lines.len() / 2;
+    while chunk > 0 {
+        for start in (0..lines.len()).step_by(chunk) {
+            let end = (start + chunk).min(lines.len());
+            let kept = lines[..start].iter().chain(&lines[end..]);
+            candidates.push(kept.map(|line| format!("{}\n", line)).collect());
+        }
+        chunk /= 2;
+    }
+    candidates
+}
+
+/// Parameters of a [`check`] run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Config {
+    pub seed: u64,
+    /// Number of inputs to generate
+    pub cases: usize,
+    /// Size of the last input, the first ones being smaller
+    pub size: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            cases: 100,
+            size: 30,
+        }
+    }
+}
+
+impl Config {
+    /// Default configuration, overridden by `AOC_SEED`, `AOC_CASES` and `AOC_SIZE`
+    pub fn from_env() -> Self {
+        fn var<T: FromStr>(name: &str, default: T) -> T {
+            std::env::var(name)
+                .ok()
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(default)
+        }
+
+        let default = Self::default();
+        Self {
+            seed: var("AOC_SEED", default.seed),
+            cases: var("AOC_CASES", default.cases),
+            size: var("AOC_SIZE", default.size),
+        }
+    }
+}
+
+/// Way in which a generated input exposed a bug
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Failure {
+    Parse(String),
+    /// A stage or a variant failed or panicked
+    Error {
+        stage: Stage,
+        error: String,
+    },
+    /// Implementations of `stage` gave different answers, listed with their name
+    Mismatch {
+        stage: Stage,
+        answers: Vec<(String, Answer)>,
+    },
+}
+
+impl Failure {
+    /// Whether both failures likely have the same cause, which is what shrinking preserves
+    fn same_kind(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Parse(_), Self::Parse(_)) => true,
+            (Self::Error { stage: a, .. }, Self::Error { stage: b, .. }) => a == b,
+            (Self::Mismatch { stage: a, .. }, Self::Mismatch { stage: b, .. }) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse(error) => write!(f, "parse: {}", error),
+            Self::Error { stage, error } => write!(f, "{}: {}", stage, error),
+            Self::Mismatch { stage, answers } => {
+                write!(f, "{}: implementations disagree", stage)?;
+                for (name, answer) in answers {
+                    write!(f, "\n  {} answered {}", name, answer)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Generated input on which a challenge failed, after shrinking
+#[derive(Clone, PartialEq, Eq)]
+pub struct Discrepancy {
+    pub seed: u64,
+    /// Index of the generated input, starting at 0
+    pub case: usize,
+    pub failure: Failure,
+    /// Smallest input found failing the same way as the generated one
+    pub data: String,
+}
+
+impl fmt::Display for Discrepancy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "generated input {} of seed {} failed, {}",
+            self.case, self.seed, self.failure
+        )?;
+        write!(f, "shrunk input:\n{}", self.data)
+    }
+}
+
+/// Shows the [`Display`](fmt::Display) output, so that unwrapping the result of [`check`] prints
+/// the failing input as is
+impl fmt::Debug for Discrepancy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl std::error::Error for Discrepancy {}
+
+/// Runs the stages of `challenge` over one input, comparing the answers of every implementation
+fn run_case<C: Generate + Entry>(challenge: &C, data: &str) -> Result<(), Failure> {
+    let input = match guard(|| Entry::parse(challenge, data), Error::Parse) {
+        Ok(input) => input,
+        Err(Error::Unimplemented) => return Ok(()),
+        Err(err) => return Err(Failure::Parse(format!("{:#}", err))),
+    };
+    for official in Stage::ALL {
+        let mut answers = Vec::new();
+        for stage in challenge.with_variants(&[official]) {
+            match guard(|| challenge.stage(stage, &input), Error::Solve) {
+                Ok(answer) => answers.push((stage.to_string(), answer)),
+                Err(Error::Unimplemented) => {}
+                Err(err) => {
+                    return Err(Failure::Error {
+                        stage,
+                        error: format!("{:#}", err),
+                    })
+                }
+            }
+        }
+        if let Some(answer) = challenge.reference(data, official) {
+            answers.push(("reference".to_owned(), answer));
+        }
+        if answers.iter().any(|(_, answer)| *answer != answers[0].1) {
+            return Err(Failure::Mismatch {
+                stage: official,
+                answers,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Maximum number of candidates tried while shrinking a failing input
+const SHRINK_BUDGET: usize = 1000;
+
+/// Greedily replaces `data` by the first smaller candidate failing like `failure`, until none does
+fn shrink<C: Generate + Entry>(
+    challenge: &C,
+    mut data: String,
+    mut failure: Failure,
+) -> (String, Failure) {
+    let mut budget = SHRINK_BUDGET;
+    'shrinking: loop {
+        for candidate in challenge.shrink(&data) {
+            if budget == 0 {
+                break 'shrinking;
+            }
+            budget -= 1;
+            if let Err(found) = run_case(challenge, &candidate) {
+                if found.same_kind(&failure) {
+                    tracing::debug!("Shrunk input to {} bytes", candidate.len());
+                    data = candidate;
+                    failure = found;
+                    continue 'shrinking;
+                }
+            }
+        }
+        break;
+    }
+    (data, failure)
+}
+
+/// Runs `challenge` over the inputs it generates from `config`, returning the first discrepancy
+/// found among its implementations
+pub fn check<C>(challenge: C, config: &Config) -> Result<(), Discrepancy>
+where
+    C: 'static + Generate + fmt::Debug + Send + Sync,
+{
+    let mut rng = Rng::seed_from_u64(config.seed);
+    for case in 0..config.cases {
+        let size = 1 + case * config.size / config.cases.max(1);
+        let data = challenge.generate(&mut rng, size);
+        if let Err(failure) = run_case(&challenge, &data) {
+            let (data, failure) = shrink(&challenge, data, failure);
+            return Err(Discrepancy {
+                seed: config.seed,
+                case,
+                failure,
+                data,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Declares the `test_generated` unit test of a day crate, running [`check`] over the challenge
+/// with the [configuration of the environment](Config::from_env)
+#[macro_export]
+macro_rules! generated {
+    ($c: expr) => {
+        #[test]
+        fn test_generated() {
+            $crate::generate::check($c, &$crate::generate::Config::from_env()).unwrap();
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng as _;
+
+    use super::{check, shrink_lines, Config, Failure, Generate, Rng};
+    use crate::{testing::Sum, Answer, Stage};
+
+    impl Generate for Sum {
+        fn generate(&self, rng: &mut Rng, size: usize) -> String {
+            (0..size)
+                .map(|_| format!("{}\n", rng.gen_range(0..60)))
+                .collect()
+        }
+
+        fn reference(&self, data: &str, stage: Stage) -> Option<Answer> {
+            let sum: u64 = data.lines().map(|l| l.parse::<u64>().unwrap()).sum();
+            (stage == Stage::Stage1).then(|| sum.into())
+        }
+    }
+
+    #[test]
+    fn test_shrink_lines() {
+        assert_eq!(
+            vec![
+                "c\nd\n",
+                "a\nb\n",
+                "b\nc\nd\n",
+                "a\nc\nd\n",
+                "a\nb\nd\n",
+                "a\nb\nc\n"
+            ],
+            shrink_lines("a\nb\nc\nd\n")
+        );
+        assert!(shrink_lines("a\n").is_empty());
+    }
+
+    #[test]
+    fn test_check() {
+        let config = Config {
+            seed: 7,
+            cases: 50,
+            size: 20,
+        };
+        let discrepancy = check(Sum, &config).unwrap_err();
+        assert_eq!(7, discrepancy.seed);
+
+        // A single number above 50 is enough for the variant to disagree
+        let number: u64 = discrepancy.data.trim().parse().unwrap();
+        assert!(number > 50);
+        let expected = Answer::from(number);
+        assert_eq!(
+            Failure::Mismatch {
+                stage: Stage::Stage1,
+                answers: vec![
+                    ("stage1".to_owned(), expected.clone()),
+                    ("stage1-capped".to_owned(), Answer::from(0)),
+                    ("reference".to_owned(), expected),
+                ],
+            },
+            discrepancy.failure
+        );
+        assert!(discrepancy.to_string().starts_with(&format!(
+            "generated input {} of seed 7 failed",
+            discrepancy.case
+        )));
+    }
+}
@@ -0,0 +1,379 @@
+//! Two-dimensional grids of cells, such as bit matrices, bingo boards or height maps.
+//!
+//! [`Grid`] wraps an [`Array2`], adding the operations puzzles keep needing: parsing, neighbours,
+//! flood fill and printing. Cells are addressed by `(row, column)` positions, starting at the top
+//! left corner, as in `ndarray`.
+
+use std::{
+    collections::VecDeque,
+    fmt,
+    ops::{Index, IndexMut},
+    str::FromStr,
+};
+
+use ndarray::{s, Array2, ArrayView1};
+
+use crate::parse::{self, ParseError, Text};
+
+/// Offsets of the neighbours sharing a side with a cell, in reading order
+const SIDES: [(isize, isize); 4] = [(-1, 0), (0, -1), (0, 1), (1, 0)];
+
+/// Offsets of the neighbours sharing a side or a corner with a cell, in reading order
+const AROUND: [(isize, isize); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    cells: Array2<T>,
+}
+
+impl<T> From<Array2<T>> for Grid<T> {
+    fn from(cells: Array2<T>) -> Self {
+        Self { cells }
+    }
+}
+
+impl<T> Grid<T> {
+    /// Grid made of `rows`, which must all have the same length
+    ///
+    /// # Panics
+    ///
+    /// When the rows have different lengths
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Self {
+        let shape = (rows.len(), rows.first().map_or(0, Vec::len));
+        assert!(
+            rows.iter().all(|row| row.len() == shape.1),
+            "Rows have different lengths"
+        );
+        let cells = rows.into_iter().flatten().collect();
+        Self {
+            cells: Array2::from_shape_vec(shape, cells).expect("The shape matches the rows"),
+        }
+    }
+
+    /// Grid of the given `(rows, columns)` shape, with the cells built from their position
+    pub fn from_fn(shape: (usize, usize), f: impl FnMut((usize, usize)) -> T) -> Self {
+        Self {
+            cells: Array2::from_shape_fn(shape, f),
+        }
+    }
+
+    /// Grid of characters, each converted by `cell`
+    pub fn parse_with<'a, E: fmt::Display>(
+        text: impl Into<Text<'a>>,
+        cell: impl FnMut(char) -> Result<T, E>,
+    ) -> Result<Self, ParseError> {
+        parse::grid(text, cell).map(Self::from_rows)
+    }
+
+    /// Grid of characters, such as `#` and `.`
+    pub fn parse_chars<'a>(text: impl Into<Text<'a>>) -> Result<Self, ParseError>
+    where
+        T: TryFrom<char>,
+        T::Error: fmt::Display,
+    {
+        parse::char_grid(text).map(Self::from_rows)
+    }
+
+    /// Grid of numbers separated by whitespace, such as a bingo board
+    pub fn parse_numbers<'a>(text: impl Into<Text<'a>>) -> Result<Self, ParseError>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        parse::number_grid(text).map(Self::from_rows)
+    }
+
+    pub fn nrows(&self) -> usize {
+        self.cells.nrows()
+    }
+
+    pub fn ncols(&self) -> usize {
+        self.cells.ncols()
+    }
+
+    /// Number of rows and columns
+    pub fn dim(&self) -> (usize, usize) {
+        self.cells.dim()
+    }
+
+    pub fn as_array(&self) -> &Array2<T> {
+        &self.cells
+    }
+
+    pub fn into_array(self) -> Array2<T> {
+        self.cells
+    }
+
+    pub fn get(&self, pos: (usize, usize)) -> Option<&T> {
+        self.cells.get(pos)
+    }
+
+    pub fn get_mut(&mut self, pos: (usize, usize)) -> Option<&mut T> {
+        self.cells.get_mut(pos)
+    }
+
+    /// Cells in reading order
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    /// Cells in reading order, along with their position
+    pub fn indexed_iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        self.cells.indexed_iter()
+    }
+
+    /// Positions of the cells for which `pred` holds, in reading order
+    pub fn positions<'a>(
+        &'a self,
+        mut pred: impl 'a + FnMut(&T) -> bool,
+    ) -> impl 'a + Iterator<Item = (usize, usize)> {
+        self.indexed_iter()
+            .filter_map(move |(pos, cell)| pred(cell).then_some(pos))
+    }
+
+    /// Grid of the same shape, with every cell converted by `f`
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            cells: self.cells.map(f),
+        }
+    }
+
+    pub fn row(&self, row: usize) -> ArrayView1<'_, T> {
+        self.cells.row(row)
+    }
+
+    pub fn column(&self, column: usize) -> ArrayView1<'_, T> {
+        self.cells.column(column)
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = ArrayView1<'_, T>> {
+        self.cells.rows().into_iter()
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = ArrayView1<'_, T>> {
+        self.cells.columns().into_iter()
+    }
+
+    /// Cells from the top left corner going down to the right, as long as the shortest side
+    pub fn diagonal(&self) -> ArrayView1<'_, T> {
+        self.cells.diag()
+    }
+
+    /// Cells from the top right corner going down to the left, as long as the shortest side
+    pub fn anti_diagonal(&self) -> ArrayView1<'_, T> {
+        self.cells.slice(s![.., ..;-1]).into_diag()
+    }
+
+    /// Grid mirrored along its diagonal, its rows becoming its columns
+    pub fn transpose(self) -> Self {
+        Self {
+            cells: self.cells.reversed_axes(),
+        }
+    }
+
+    /// Position `offset` away from `pos`, if it lies in the grid
+    fn offset(&self, pos: (usize, usize), offset: (isize, isize)) -> Option<(usize, usize)> {
+        let row = pos.0.checked_add_signed(offset.0)?;
+        let column = pos.1.checked_add_signed(offset.1)?;
+        (row < self.nrows() && column < self.ncols()).then_some((row, column))
+    }
+
+    /// Positions of the up to 4 cells sharing a side with `pos`, in reading order
+    pub fn neighbours4(&self, pos: (usize, usize)) -> impl '_ + Iterator<Item = (usize, usize)> {
+        SIDES
+            .iter()
+            .filter_map(move |&offset| self.offset(pos, offset))
+    }
+
+    /// Positions of the up to 8 cells sharing a side or a corner with `pos`, in reading order
+    pub fn neighbours8(&self, pos: (usize, usize)) -> impl '_ + Iterator<Item = (usize, usize)> {
+        AROUND
+            .iter()
+            .filter_map(move |&offset| self.offset(pos, offset))
+    }
+
+    /// Positions of the region of cells for which `inside` holds which contains `start`, going
+    /// from cell to cell through their sides. Cells are listed by distance to `start`, and none
+    /// at all when `start` is not inside.
+    pub fn flood_fill(
+        &self,
+        start: (usize, usize),
+        mut inside: impl FnMut(&T) -> bool,
+    ) -> Vec<(usize, usize)> {
+        let mut region = Vec::new();
+        if !self.get(start).is_some_and(&mut inside) {
+            return region;
+        }
+        let mut seen = Array2::from_elem(self.dim(), false);
+        let mut queue = VecDeque::from([start]);
+        seen[start] = true;
+        while let Some(pos) = queue.pop_front() {
+            region.push(pos);
+            for next in self.neighbours4(pos) {
+                if !seen[next] && inside(&self[next]) {
+                    seen[next] = true;
+                    queue.push_back(next);
+                }
+            }
+        }
+        region
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    /// Grid of the given `(rows, columns)` shape, filled with `value`
+    pub fn from_elem(shape: (usize, usize), value: T) -> Self {
+        Self {
+            cells: Array2::from_elem(shape, value),
+        }
+    }
+}
+
+impl Grid<u8> {
+    /// Grid of digits, such as a height map
+    pub fn parse_digits<'a>(text: impl Into<Text<'a>>) -> Result<Self, ParseError> {
+        parse::digit_grid(text).map(Self::from_rows)
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: (usize, usize)) -> &T {
+        &self.cells[pos]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, pos: (usize, usize)) -> &mut T {
+        &mut self.cells[pos]
+    }
+}
+
+/// One row per line. Cells are right-aligned and separated by a space, unless they all fit in a
+/// single character: bit matrices and maps then look like the puzzle inputs.
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let cells = self.cells.map(ToString::to_string);
+        let width = cells.iter().map(|c| c.chars().count()).max().unwrap_or(0);
+        let separator = if width > 1 { " " } else { "" };
+        for (i, row) in cells.rows().into_iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            for (j, cell) in row.iter().enumerate() {
+                if j > 0 {
+                    f.write_str(separator)?;
+                }
+                write!(f, "{:>width$}", cell, width = width)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Grid;
+
+    const HEIGHTS: &str = "2199943210\n3987894921\n9856789892\n8767896789\n9899965678\n";
+
+    #[test]
+    fn test_parsing() {
+        let grid = Grid::parse_digits(HEIGHTS).unwrap();
+        assert_eq!((5, 10), grid.dim());
+        assert_eq!(1, grid[(0, 1)]);
+        assert_eq!(None, grid.get((5, 0)));
+
+        let board = Grid::<u32>::parse_numbers("22 13 17\n 8  2 23\n").unwrap();
+        assert_eq!(vec![22, 8], board.column(0).to_vec());
+        let err = Grid::<u32>::parse_numbers("22 13\n8\n").unwrap_err();
+        assert_eq!(
+            "line 2, column 1: Row has 1 cells, expected 2",
+            err.to_string()
+        );
+
+        let map = Grid::parse_with("#.\n.#\n", |c| match c {
+            '#' => Ok(true),
+            '.' => Ok(false),
+            _ => Err("Unknown cell"),
+        })
+        .unwrap();
+        assert_eq!(
+            vec![true, false, false, true],
+            map.iter().copied().collect::<Vec<_>>()
+        );
+        assert!(Grid::<char>::parse_chars("").is_err());
+    }
+
+    #[test]
+    fn test_views() {
+        let grid = Grid::from_fn((3, 4), |(row, col)| 10 * row + col);
+        assert_eq!(vec![10, 11, 12, 13], grid.row(1).to_vec());
+        assert_eq!(vec![2, 12, 22], grid.column(2).to_vec());
+        assert_eq!(3, grid.rows().count());
+        assert_eq!(4, grid.columns().count());
+        assert_eq!(vec![0, 11, 22], grid.diagonal().to_vec());
+        assert_eq!(vec![3, 12, 21], grid.anti_diagonal().to_vec());
+
+        let transposed = grid.clone().transpose();
+        assert_eq!((4, 3), transposed.dim());
+        assert_eq!(grid.row(1), transposed.column(1));
+        assert_eq!(grid, transposed.transpose());
+
+        assert_eq!(
+            vec![(0, 2), (2, 3)],
+            grid.positions(|&cell| cell % 7 == 2).collect::<Vec<_>>()
+        );
+        assert_eq!(Grid::from_elem((3, 4), true), grid.map(|&cell| cell < 30));
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = Grid::from_elem((3, 4), 0);
+        assert_eq!(
+            vec![(0, 1), (1, 0)],
+            grid.neighbours4((0, 0)).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![(0, 2), (1, 1), (1, 3), (2, 2)],
+            grid.neighbours4((1, 2)).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![(1, 2), (1, 3), (2, 2)],
+            grid.neighbours8((2, 3)).collect::<Vec<_>>()
+        );
+        assert_eq!(8, grid.neighbours8((1, 1)).count());
+    }
+
+    #[test]
+    fn test_flood_fill() {
+        let grid = Grid::parse_digits(HEIGHTS).unwrap();
+        let basin = grid.flood_fill((0, 0), |&height| height < 9);
+        assert_eq!(vec![(0, 0), (0, 1), (1, 0)], basin);
+        let mut basin = grid.flood_fill((0, 9), |&height| height < 9);
+        assert_eq!(9, basin.len());
+        basin.sort();
+        assert_eq!((0, 5), basin[0]);
+        assert_eq!(14, grid.flood_fill((2, 2), |&height| height < 9).len());
+        assert!(grid.flood_fill((0, 2), |&height| height < 9).is_empty());
+    }
+
+    #[test]
+    fn test_display() {
+        let grid = Grid::parse_digits("219\n398\n").unwrap();
+        assert_eq!("219\n398", grid.to_string());
+        let board = Grid::<u32>::parse_numbers("22 13 17\n 8  2 23\n").unwrap();
+        assert_eq!("22 13 17\n 8  2 23", board.to_string());
+        assert_eq!("", Grid::<u8>::from_rows(Vec::new()).to_string());
+    }
+}
@@ -0,0 +1,168 @@
+//! Location and loading of the puzzle input.
+
+use std::{
+    fmt,
+    io::Read,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use anyhow::Context;
+use tokio::io::AsyncReadExt;
+use tracing::Instrument;
+
+/// Where the puzzle input is read from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    /// Standard input, selected with `-` on the command line
+    Stdin,
+    File(PathBuf),
+}
+
+impl FromStr for Source {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "-" => Self::Stdin,
+            path => Self::File(PathBuf::from(path)),
+        })
+    }
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Stdin => write!(f, "<stdin>"),
+            Self::File(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+impl Source {
+    /// Conventional locations of the input of `day`, relative to the working directory, in order
+    /// of preference: `inputs/dayNN.txt`, the `data.txt` of the day crate when running from the
+    /// workspace, and `data.txt` when running from the day crate itself.
+    pub fn candidates(day: u8) -> Vec<PathBuf> {
+        Self::candidates_in(Path::new("."), day)
+    }
+
+    fn candidates_in(dir: &Path, day: u8) -> Vec<PathBuf> {
+        let name = format!("day{:02}", day);
+        let mut candidates = vec![
+            dir.join("inputs").join(format!("{}.txt", name)),
+            dir.join(&name).join("data.txt"),
+        ];
+        let in_crate = dir
+            .canonicalize()
+            .ok()
+            .and_then(|dir| dir.file_name().map(|n| n == name.as_str()))
+            .unwrap_or(false);
+        if in_crate {
+            candidates.push(dir.join("data.txt"));
+        }
+        candidates
+    }
+
+    /// Finds the input of `day` among the [conventional locations](Source::candidates)
+    pub fn locate(day: u8) -> anyhow::Result<Self> {
+        Self::locate_in(Path::new("."), day)
+    }
+
+    pub(crate) fn locate_in(dir: &Path, day: u8) -> anyhow::Result<Self> {
+        let candidates = Self::candidates_in(dir, day);
+        match candidates.iter().find(|path| path.is_file()) {
+            Some(path) => Ok(Self::File(path.clone())),
+            None => anyhow::bail!(
+                "No input found for day {}, tried {}",
+                day,
+                candidates
+                    .iter()
+                    .map(|p| p.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        }
+    }
+
+    pub async fn read(&self) -> anyhow::Result<String> {
+        async {
+            match self {
+                Self::Stdin => {
+                    let mut data = String::new();
+                    tokio::io::stdin()
+                        .read_to_string(&mut data)
+                        .await
+                        .map(|_| data)
+                }
+                Self::File(path) => tokio::fs::read_to_string(path).await,
+            }
+        }
+        .instrument(tracing::trace_span!("read-data", source = %self))
+        .await
+        .with_context(|| format!("Cannot read {}", self))
+    }
+
+    pub fn read_blocking(&self) -> anyhow::Result<String> {
+        match self {
+            Self::Stdin => {
+                let mut data = String::new();
+                std::io::stdin().read_to_string(&mut data).map(|_| data)
+            }
+            Self::File(path) => std::fs::read_to_string(path),
+        }
+        .with_context(|| format!("Cannot read {}", self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::Source;
+    use crate::testing::TempDir;
+
+    #[test]
+    fn test_parsing() {
+        assert_eq!(Ok(Source::Stdin), "-".parse());
+        assert_eq!(
+            Ok(Source::File(PathBuf::from("day01/data.txt"))),
+            "day01/data.txt".parse()
+        );
+    }
+
+    #[test]
+    fn test_locate_preference() {
+        let dir = TempDir::new("workspace");
+        std::fs::create_dir_all(dir.join("inputs")).unwrap();
+        std::fs::create_dir_all(dir.join("day03")).unwrap();
+        std::fs::write(dir.join("day03/data.txt"), "").unwrap();
+
+        assert_eq!(
+            Source::File(dir.join("day03/data.txt")),
+            Source::locate_in(&dir, 3).unwrap()
+        );
+
+        std::fs::write(dir.join("inputs/day03.txt"), "").unwrap();
+        assert_eq!(
+            Source::File(dir.join("inputs/day03.txt")),
+            Source::locate_in(&dir, 3).unwrap()
+        );
+        assert!(Source::locate_in(&dir, 4).is_err());
+    }
+
+    #[test]
+    fn test_locate_in_crate() {
+        // The directory of the day crate is recognized by its name
+        let tmp = TempDir::new("crate");
+        let dir = tmp.join("day07");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("data.txt"), "").unwrap();
+
+        assert_eq!(
+            Source::File(dir.join("data.txt")),
+            Source::locate_in(&dir, 7).unwrap()
+        );
+        assert!(Source::locate_in(&dir, 8).is_err());
+    }
+}
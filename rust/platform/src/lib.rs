@@ -1,19 +1,112 @@
-use std::{fmt::Debug, path::PathBuf, str::FromStr};
+use serde::{Serialize, Serializer};
+use std::{fmt, fmt::Debug, process::ExitCode, str::FromStr, sync::Arc, time::Instant};
 use structopt::StructOpt;
-use tracing::Instrument;
 
 pub use anyhow;
-use tokio::runtime::Builder;
+pub use ndarray;
+pub use rand;
+
+pub use answer::Answer;
+use bench::BenchOptions;
+use cache::CacheOptions;
+pub use error::{Error, ExitStatus};
+use exec::{exec_bench, run_stages, RuntimeOptions};
+pub use input::Source;
+use logging::LogOptions;
+use registry::Entry;
+pub use registry::Registry;
+use report::Summary;
+use verify::VerifyOptions;
+
+mod answer;
+pub mod bench;
+mod cache;
+pub mod client;
+mod error;
+pub mod examples;
+mod exec;
+pub mod generate;
+pub mod grid;
+mod input;
+mod logging;
+pub mod memory;
+mod panic;
+pub mod parse;
+mod registry;
+mod report;
+pub mod runner;
+pub mod search;
+#[cfg(test)]
+mod testing;
+pub mod verify;
+
+#[cfg(feature = "count-alloc")]
+#[global_allocator]
+static GLOBAL: memory::Counting = memory::Counting;
 
 pub trait Challenge {
-    fn stage1(self, data: String) -> anyhow::Result<()>;
-    fn stage2(self, data: String) -> anyhow::Result<()>;
+    /// Day of the advent calendar this challenge solves
+    const DAY: u8;
+    /// Parsed puzzle input, shared by every stage
+    type Input: 'static + Send + Sync;
+
+    fn parse(&self, data: &str) -> anyhow::Result<Self::Input>;
+    fn stage1(&self, input: &Self::Input) -> anyhow::Result<Answer>;
+    fn stage2(&self, input: &Self::Input) -> anyhow::Result<Answer>;
+
+    /// Additional stages, such as alternate algorithms or debug views, run by
+    /// [`Challenge::custom`]. Alternate implementations of an official stage are declared with
+    /// [`CustomStage::variant`] to be checked against it by `--cross-check`.
+    const CUSTOM_STAGES: &'static [CustomStage] = &[];
+
+    /// Runs the custom stage `name`, one of [`Challenge::CUSTOM_STAGES`]. Any other name is an
+    /// error, rather than a stage left to implement.
+    fn custom(&self, name: &str, _input: &Self::Input) -> anyhow::Result<Answer> {
+        anyhow::bail!("unknown custom stage {}", name)
+    }
+}
+
+/// Stage of a challenge beyond the two official ones, selected by its name on the command line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CustomStage {
+    pub name: &'static str,
+    /// One-line description, shown in `--help`
+    pub about: &'static str,
+    /// Official stage this stage is another implementation of, expected to give the same answer
+    pub variant_of: Option<Stage>,
+}
+
+impl CustomStage {
+    pub const fn new(name: &'static str, about: &'static str) -> Self {
+        Self {
+            name,
+            about,
+            variant_of: None,
+        }
+    }
+
+    /// Declares an alternate implementation of `stage`, such as a naive version of an optimized
+    /// solver
+    pub const fn variant(stage: Stage, name: &'static str, about: &'static str) -> Self {
+        Self {
+            name,
+            about,
+            variant_of: Some(stage),
+        }
+    }
 }
 
-#[derive(Debug)]
-enum Stage {
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Stage {
+    #[default]
     Stage1,
     Stage2,
+    /// Stage declared in [`Challenge::CUSTOM_STAGES`]
+    Custom(&'static str),
+}
+
+impl Stage {
+    pub const ALL: [Stage; 2] = [Stage::Stage1, Stage::Stage2];
 }
 
 impl FromStr for Stage {
@@ -21,62 +114,249 @@ impl FromStr for Stage {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         Ok(match s.trim() {
-            "stage1" => Self::Stage1,
-            "stage2" => Self::Stage2,
+            "stage1" | "1" => Self::Stage1,
+            "stage2" | "2" => Self::Stage2,
             _ => anyhow::bail!("Cannot parse stage from {:?}", s),
         })
     }
 }
 
-impl Default for Stage {
-    fn default() -> Self {
-        Self::Stage1
+impl fmt::Display for Stage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Stage1 => write!(f, "stage1"),
+            Self::Stage2 => write!(f, "stage2"),
+            Self::Custom(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+impl Serialize for Stage {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// Stages selected on the command line, either `all` or a comma-separated list such as
+/// `stage1,stage2`. Custom stages being declared by the challenges, the names are only checked
+/// once [resolved](StageSelection::resolve) against a challenge.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StageSelection(Vec<String>);
+
+impl StageSelection {
+    /// Selects the official stages
+    pub fn all() -> Self {
+        Self(Stage::ALL.iter().map(Stage::to_string).collect())
+    }
+
+    /// Finds the selected stages among the `available` ones
+    pub fn resolve(&self, available: &[Stage]) -> anyhow::Result<Vec<Stage>> {
+        self.0
+            .iter()
+            .map(|name| {
+                available
+                    .iter()
+                    .find(|stage| stage.to_string() == *name)
+                    .copied()
+                    .ok_or_else(|| {
+                        let names: Vec<_> = available.iter().map(Stage::to_string).collect();
+                        anyhow::anyhow!(
+                            "No stage named {:?}, expected one of {}",
+                            name,
+                            names.join(", ")
+                        )
+                    })
+            })
+            .collect()
+    }
+}
+
+impl FromStr for StageSelection {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.trim() == "all" {
+            return Ok(Self::all());
+        }
+        let mut names = Vec::new();
+        for name in s.split(',').map(str::trim) {
+            if name.is_empty() {
+                anyhow::bail!("Empty stage name in {:?}", s);
+            }
+            // Official stages may be abbreviated to their number
+            let name = match Stage::from_str(name) {
+                Ok(stage) => stage.to_string(),
+                Err(_) => name.to_owned(),
+            };
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+        Ok(Self(names))
     }
 }
 
 #[derive(Debug, StructOpt)]
 struct Options {
-    /// File pointing to the challenge data file
+    /// File pointing to the challenge data file, `-` reading from stdin. When omitted, the file is
+    /// looked up in `inputs/dayNN.txt`, then in the `data.txt` of the day crate
     #[structopt(name = "data file")]
-    filename: PathBuf,
-    /// Stage to run
+    filename: Option<Source>,
+    /// Stages to run: a stage name, a comma-separated list of stages, or `all` for `stage1` and
+    /// `stage2`
     #[structopt(short, long, default_value = "stage1")]
-    challenge: Stage,
+    challenge: StageSelection,
+    /// Also run the variants of the selected stages, failing unless they give the same answers.
+    /// The cache is bypassed so that the time of every variant is reported
+    #[structopt(long)]
+    cross_check: bool,
+    #[structopt(flatten)]
+    verify: VerifyOptions,
+    #[structopt(flatten)]
+    bench: BenchOptions,
+    #[structopt(flatten)]
+    runtime: RuntimeOptions,
+    #[structopt(flatten)]
+    cache: CacheOptions,
+    #[structopt(flatten)]
+    log: LogOptions,
 }
 
-#[tracing::instrument]
-pub fn run<C: 'static + Debug + Challenge + Send>(challenge: C) -> anyhow::Result<()> {
-    tracing_subscriber::fmt::init();
-
-    let rt = Builder::new_current_thread()
-        .thread_name("aoc-platform-thread")
-        .max_blocking_threads(1)
-        .build()?;
-    let args: Options = Options::from_args();
-    rt.block_on(async {
-        let data = tokio::fs::read_to_string(args.filename)
-            .instrument(tracing::trace_span!("read-data"))
-            .await?;
-        match args.challenge {
-            Stage::Stage1 => {
-                rt.spawn_blocking(move || challenge.stage1(data))
-                    .instrument(tracing::trace_span!("exec-challenge"))
-                    .await?
+/// Entry point of the per-day binaries: parses the command line and runs `challenge`. The exit
+/// code tells apart failed stages, wrong answers and stages which are not implemented yet, see
+/// [`ExitStatus`].
+pub fn run<C: 'static + Debug + Challenge + Send + Sync>(challenge: C) -> ExitCode {
+    let help = registry::custom_stages_help(
+        C::CUSTOM_STAGES
+            .iter()
+            .map(|stage| (stage.name.to_owned(), stage)),
+    );
+    let app = Options::clap();
+    let args = match &help {
+        Some(help) => Options::from_clap(&app.after_help(help.as_str()).get_matches()),
+        None => Options::from_clap(&app.get_matches()),
+    };
+    let status = args.log.init().and_then(|_trace| {
+        panic::install_hook();
+        try_run(challenge, args)
+    });
+    ExitStatus::report(status)
+}
+
+#[tracing::instrument(skip(args))]
+fn try_run<C: 'static + Debug + Challenge + Send + Sync>(
+    challenge: C,
+    args: Options,
+) -> anyhow::Result<ExitStatus> {
+    let rt = args.runtime.runtime()?;
+    let answers = args.verify.load()?;
+    let challenge: Arc<dyn Entry> = Arc::new(challenge);
+    let mut stages = args.challenge.resolve(&challenge.stages())?;
+    if args.cross_check {
+        stages = challenge.with_variants(&stages);
+    }
+    let stages = &stages;
+    let source = match args.filename {
+        Some(source) => source,
+        None => Source::locate(C::DAY)?,
+    };
+    let status = rt.block_on(async {
+        let limits = args.runtime.limits();
+        if let Some(iterations) = args.bench.bench {
+            let mut reports = Vec::new();
+            for &stage in stages {
+                let report = exec_bench(
+                    challenge.clone(),
+                    stage,
+                    source.clone(),
+                    iterations,
+                    &limits,
+                )
+                .await?;
+                println!("{}", report);
+                reports.push(report);
             }
-            Stage::Stage2 => {
-                rt.spawn_blocking(move || challenge.stage2(data))
-                    .instrument(tracing::trace_span!("exec-challenge"))
-                    .await?
+            args.bench.write_json(&reports)?;
+            return Ok(ExitStatus::Success);
+        }
+
+        let start = Instant::now();
+        let cache = args.cache.open().filter(|_| !args.cross_check);
+        let outcome =
+            run_stages(challenge.clone(), source, stages, &limits, cache.as_deref()).await;
+        if let Some(cache) = cache {
+            if let Err(err) = cache.save() {
+                tracing::warn!("{:#}", err);
             }
         }
-    })
+        let mut summary = Summary::default();
+        let tally = outcome.report(
+            &*challenge,
+            "",
+            stages.len(),
+            answers.as_ref(),
+            &mut summary,
+        );
+        summary.wall(start.elapsed());
+        if stages.len() > 1 {
+            println!("\n{}", summary);
+        }
+        Ok(tally.status())
+    });
+    // Stages which timed out may still be running, they are abandoned instead of waited for
+    rt.shutdown_background();
+    status
 }
 
 #[macro_export]
 macro_rules! challenge {
     ($c: expr) => {
-        fn main() -> $crate::anyhow::Result<()> {
+        fn main() -> ::std::process::ExitCode {
             $crate::run($c)
         }
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Stage, StageSelection};
+
+    #[test]
+    fn test_stage_selection() {
+        let available = [Stage::Stage1, Stage::Stage2, Stage::Custom("visualize")];
+
+        let all: StageSelection = "all".parse().unwrap();
+        assert_eq!(
+            vec![Stage::Stage1, Stage::Stage2],
+            all.resolve(&available).unwrap()
+        );
+
+        let list: StageSelection = "stage2,1,stage2".parse().unwrap();
+        assert_eq!(
+            vec![Stage::Stage2, Stage::Stage1],
+            list.resolve(&available).unwrap()
+        );
+
+        let custom: StageSelection = "visualize,2".parse().unwrap();
+        assert_eq!(
+            vec![Stage::Custom("visualize"), Stage::Stage2],
+            custom.resolve(&available).unwrap()
+        );
+
+        let unknown: StageSelection = "stage1,stage3".parse().unwrap();
+        let err = unknown.resolve(&available).unwrap_err();
+        assert_eq!(
+            "No stage named \"stage3\", expected one of stage1, stage2, visualize",
+            err.to_string()
+        );
+        assert!("stage1,".parse::<StageSelection>().is_err());
+    }
+
+    #[test]
+    fn test_stage_serialization() {
+        assert_eq!(
+            "[\"stage1\",\"stats\"]",
+            serde_json::to_string(&[Stage::Stage1, Stage::Custom("stats")]).unwrap()
+        );
+    }
+}
@@ -0,0 +1,275 @@
+//! Installation of the tracing subscriber, which also receives the records of the `log` crate
+//! emitted by the challenges, and can export every span to a trace file.
+
+use std::{
+    fs::File,
+    io::BufWriter,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use anyhow::Context;
+use structopt::StructOpt;
+use tracing::Subscriber;
+use tracing_chrome::ChromeLayerBuilder;
+use tracing_flame::FlameLayer;
+use tracing_subscriber::{
+    filter::{Directive, LevelFilter},
+    prelude::*,
+    EnvFilter,
+};
+
+/// Format of the file spans are exported to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TraceFormat {
+    /// Chrome trace events, to open in `chrome://tracing` or Perfetto
+    Chrome,
+    /// Folded stacks, to render with `inferno-flamegraph` or `flamegraph.pl`
+    Folded,
+}
+
+impl FromStr for TraceFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "chrome" => Self::Chrome,
+            "folded" => Self::Folded,
+            _ => anyhow::bail!("Unknown trace format {:?}, expected chrome or folded", s),
+        })
+    }
+}
+
+/// Flushes the trace file, if any, when dropped
+#[must_use]
+#[derive(Default)]
+pub(crate) struct TraceGuard {
+    _chrome: Option<tracing_chrome::FlushGuard>,
+    _folded: Option<tracing_flame::FlushGuard<BufWriter<File>>>,
+}
+
+#[derive(Debug, StructOpt)]
+pub(crate) struct LogOptions {
+    /// Log more, may be repeated: `-v` shows info records, `-vv` debug and `-vvv` trace ones
+    #[structopt(short, long, global = true, parse(from_occurrences))]
+    pub verbose: u8,
+    /// Log less, may be repeated: `-q` only shows errors and `-qq` nothing at all
+    #[structopt(short, long, global = true, parse(from_occurrences))]
+    pub quiet: u8,
+    /// Filter directive for a module, such as `day02=debug`, applied on top of `-v`, `-q` and
+    /// `RUST_LOG`. May be repeated
+    #[structopt(long = "log", name = "directive", global = true, number_of_values = 1)]
+    pub directives: Vec<Directive>,
+    /// Write every span, whatever the log level, to the given file
+    #[structopt(long, name = "trace file", global = true)]
+    pub trace: Option<PathBuf>,
+    /// Format of the trace file: `chrome` trace events or `folded` stacks
+    #[structopt(long, name = "trace format", global = true, default_value = "chrome")]
+    pub trace_format: TraceFormat,
+}
+
+impl LogOptions {
+    /// Level of the records shown for modules without a directive, warnings by default
+    pub fn level(&self) -> LevelFilter {
+        match i16::from(self.verbose) - i16::from(self.quiet) {
+            i16::MIN..=-2 => LevelFilter::OFF,
+            -1 => LevelFilter::ERROR,
+            0 => LevelFilter::WARN,
+            1 => LevelFilter::INFO,
+            2 => LevelFilter::DEBUG,
+            _ => LevelFilter::TRACE,
+        }
+    }
+
+    fn filter(&self) -> EnvFilter {
+        self.directives.iter().cloned().fold(
+            EnvFilter::builder()
+                .with_default_directive(self.level().into())
+                .from_env_lossy(),
+            EnvFilter::add_directive,
+        )
+    }
+
+    /// Builds the subscriber, logging to stderr so that logs do not mix with the answers, and
+    /// exporting spans to the trace file if requested
+    fn subscriber(&self) -> anyhow::Result<(impl Subscriber + Send + Sync, TraceGuard)> {
+        let mut guard = TraceGuard::default();
+        let (mut chrome, mut folded) = (None, None);
+        match (&self.trace, self.trace_format) {
+            (None, _) => {}
+            (Some(path), TraceFormat::Chrome) => {
+                let (layer, flush) = ChromeLayerBuilder::new()
+                    .writer(create(path)?)
+                    .include_args(true)
+                    .build();
+                chrome = Some(layer);
+                guard._chrome = Some(flush);
+            }
+            (Some(path), TraceFormat::Folded) => {
+                let layer = FlameLayer::new(BufWriter::new(create(path)?));
+                guard._folded = Some(layer.flush_on_drop());
+                folded = Some(layer);
+            }
+        }
+        let subscriber = tracing_subscriber::registry()
+            .with(
+                tracing_subscriber::fmt::layer()
+                    .with_writer(std::io::stderr)
+                    .with_filter(self.filter()),
+            )
+            .with(chrome)
+            .with(folded);
+        Ok((subscriber, guard))
+    }
+
+    /// Installs the subscriber, to which records of the `log` crate are forwarded as well. The
+    /// returned guard must be kept until the end of the program for the trace file to be complete.
+    pub fn init(&self) -> anyhow::Result<TraceGuard> {
+        let (subscriber, guard) = self.subscriber()?;
+        subscriber.try_init()?;
+        Ok(guard)
+    }
+}
+
+fn create(path: &Path) -> anyhow::Result<File> {
+    File::create(path).with_context(|| format!("Cannot create trace file {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{num::NonZeroUsize, sync::Arc};
+
+    use structopt::StructOpt;
+    use tracing_subscriber::filter::LevelFilter;
+
+    use super::{LogOptions, TraceFormat};
+    use crate::{
+        exec::{run_stages, Limits, Outcome},
+        testing::{Fake, TempDir},
+        Source, Stage,
+    };
+
+    fn parse(args: &[&str]) -> LogOptions {
+        LogOptions::from_iter_safe(std::iter::once("test").chain(args.iter().copied())).unwrap()
+    }
+
+    #[test]
+    fn test_level() {
+        assert_eq!(LevelFilter::WARN, parse(&[]).level());
+        assert_eq!(LevelFilter::INFO, parse(&["-v"]).level());
+        assert_eq!(LevelFilter::TRACE, parse(&["-vvvv"]).level());
+        assert_eq!(LevelFilter::ERROR, parse(&["-q"]).level());
+        assert_eq!(LevelFilter::OFF, parse(&["-qqq"]).level());
+        assert_eq!(LevelFilter::WARN, parse(&["-vq"]).level());
+    }
+
+    #[test]
+    fn test_directives() {
+        let options = parse(&["--log", "day02=debug", "--log", "day05::line=trace"]);
+        assert_eq!(
+            vec!["day02=debug", "day05::line=trace"],
+            options
+                .directives
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+        );
+        assert!(LogOptions::from_iter_safe(["test", "--log", "day02=loud"]).is_err());
+    }
+
+    #[test]
+    fn test_trace_format() {
+        assert_eq!(TraceFormat::Chrome, parse(&[]).trace_format);
+        assert_eq!(
+            TraceFormat::Folded,
+            parse(&["--trace", "out.folded", "--trace-format", "folded"]).trace_format
+        );
+        assert!("svg".parse::<TraceFormat>().is_err());
+    }
+
+    fn trace(format: &str) -> String {
+        let dir = TempDir::new("trace");
+        let path = dir.join(format!("trace.{}", format));
+        let options = parse(&["--trace", path.to_str().unwrap(), "--trace-format", format]);
+        let (subscriber, guard) = options.subscriber().unwrap();
+        tracing::subscriber::with_default(subscriber, || {
+            let _outer = tracing::trace_span!("exec-challenge", day = 5).entered();
+            let _inner = tracing::trace_span!("intersect").entered();
+        });
+        drop(guard);
+        std::fs::read_to_string(path).unwrap()
+    }
+
+    #[test]
+    fn test_chrome_trace() {
+        let events: Vec<serde_json::Value> = serde_json::from_str(&trace("chrome")).unwrap();
+        let names: Vec<_> = events
+            .iter()
+            .filter(|e| e["ph"] == "B")
+            .map(|e| e["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(vec!["exec-challenge", "intersect"], names);
+    }
+
+    #[test]
+    fn test_init() {
+        // The global subscriber can only be installed once per process, by this test alone. It
+        // receives the records of the `log` crate and the events of the blocking threads.
+        let dir = TempDir::new("init");
+        let path = dir.join("trace.json");
+        let data = dir.join("day03.txt");
+        std::fs::write(&data, "abc\n").unwrap();
+        let guard = parse(&["--trace", path.to_str().unwrap()]).init().unwrap();
+        let rt = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let outcome = rt.block_on(async {
+            let limits = Limits::new(NonZeroUsize::MIN, None);
+            let source = Source::File(data);
+            run_stages(Arc::new(Fake::<3>), source, &[Stage::Stage1], &limits, None).await
+        });
+        assert!(matches!(outcome, Outcome::Solved { .. }), "{:?}", outcome);
+        drop(guard);
+
+        let events: Vec<serde_json::Value> =
+            serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+        for (span, message) in [
+            ("parse-input", "Parsing 4 bytes"),
+            ("exec-challenge", "Counting 3 bytes"),
+        ] {
+            let event = events
+                .iter()
+                .find(|e| e["ph"] == "i" && e["args"]["message"] == message)
+                .unwrap_or_else(|| panic!("No event {:?} in {:?}", message, events));
+            let ts = event["ts"].as_f64().unwrap();
+            let around = |ph: &str, before: bool| {
+                events.iter().any(|e| {
+                    e["ph"] == ph
+                        && e["name"] == span
+                        && e["tid"] == event["tid"]
+                        && (e["ts"].as_f64().unwrap() <= ts) == before
+                })
+            };
+            assert!(
+                around("B", true) && around("E", false),
+                "{:?} outside of {}: {:?}",
+                message,
+                span,
+                events
+            );
+        }
+    }
+
+    #[test]
+    fn test_folded_trace() {
+        let folded = trace("folded");
+        assert!(
+            folded
+                .lines()
+                .any(|line| line.contains("::exec-challenge:") && line.contains("::intersect:")),
+            "{}",
+            folded
+        );
+    }
+}
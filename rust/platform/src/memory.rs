@@ -0,0 +1,223 @@
+//! Opt-in accounting of heap allocations. Enabling the `count-alloc` feature of the platform
+//! installs [`Counting`] as the global allocator, and benchmarks then report the allocations of
+//! every phase, for instance with `cargo run --features platform/count-alloc -- --bench 10`.
+//!
+//! The peak resident size of every phase is reported as well on Linux, where the peak of the
+//! process can be reset before each phase.
+//!
+//! The counters are shared by every thread, so phases running concurrently are accounted
+//! together. Benchmarks run one phase at a time.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    fmt,
+    sync::atomic::{AtomicUsize, Ordering::Relaxed},
+};
+
+use serde::Serialize;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+/// Allocator counting the allocations made through the system allocator
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Counting;
+
+fn record(size: usize) {
+    ALLOCATIONS.fetch_add(1, Relaxed);
+    ALLOCATED.fetch_add(size, Relaxed);
+    let current = CURRENT.fetch_add(size, Relaxed) + size;
+    PEAK.fetch_max(current, Relaxed);
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            record(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            record(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        CURRENT.fetch_sub(layout.size(), Relaxed);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            CURRENT.fetch_sub(layout.size(), Relaxed);
+            record(new_size);
+        }
+        new_ptr
+    }
+}
+
+/// Whether the counting allocator is installed, without which [`measure`] reports nothing
+pub fn is_enabled() -> bool {
+    cfg!(feature = "count-alloc")
+}
+
+/// Heap usage of a phase
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Usage {
+    /// Number of allocations, reallocations included
+    pub allocations: usize,
+    /// Total size of the allocations
+    pub bytes: usize,
+    /// Highest amount of heap memory held at once by the phase, above what was held before it
+    pub peak_heap_bytes: usize,
+    /// Highest resident size of the process during the phase, its whole memory included. `None`
+    /// where it cannot be measured
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub peak_resident_bytes: Option<usize>,
+}
+
+/// Resident size of the process, as reported by Linux in `/proc/self/status`
+mod resident {
+    fn status(field: &str) -> Option<usize> {
+        let status = std::fs::read_to_string("/proc/self/status").ok()?;
+        let value = status
+            .lines()
+            .find_map(|line| line.strip_prefix(field)?.strip_prefix(':'))?;
+        let kib: usize = value.trim().strip_suffix("kB")?.trim().parse().ok()?;
+        Some(kib * 1024)
+    }
+
+    /// Resets the peak resident size to the current one, which is returned
+    pub fn reset_peak() -> Option<usize> {
+        std::fs::write("/proc/self/clear_refs", "5").ok()?;
+        status("VmRSS")
+    }
+
+    /// Highest resident size since the last reset
+    pub fn peak() -> Option<usize> {
+        status("VmHWM")
+    }
+}
+
+/// Formats a size in bytes with a binary prefix
+struct Bytes(usize);
+
+impl fmt::Display for Bytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+        let mut value = self.0 as f64;
+        let mut unit = 0;
+        while value >= 1024. && unit + 1 < UNITS.len() {
+            value /= 1024.;
+            unit += 1;
+        }
+        if unit == 0 {
+            write!(f, "{}B", self.0)
+        } else {
+            write!(f, "{:.1}{}", value, UNITS[unit])
+        }
+    }
+}
+
+impl fmt::Display for Usage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let resident = self
+            .peak_resident_bytes
+            .map_or_else(|| "-".to_owned(), |bytes| Bytes(bytes).to_string());
+        write!(
+            f,
+            "allocs {:>8}  bytes {:>9}  heap peak {:>9}  resident peak {:>9}",
+            self.allocations,
+            Bytes(self.bytes).to_string(),
+            Bytes(self.peak_heap_bytes).to_string(),
+            resident
+        )
+    }
+}
+
+/// Runs `f`, measuring the allocations it makes and the resident size it reaches. The resident
+/// size is only measured along with the allocations, reading it taking system calls.
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, Usage) {
+    let resident = is_enabled() && resident::reset_peak().is_some();
+    let allocations = ALLOCATIONS.load(Relaxed);
+    let bytes = ALLOCATED.load(Relaxed);
+    let current = CURRENT.load(Relaxed);
+    PEAK.store(current, Relaxed);
+
+    let value = f();
+    let usage = Usage {
+        allocations: ALLOCATIONS.load(Relaxed) - allocations,
+        bytes: ALLOCATED.load(Relaxed) - bytes,
+        peak_heap_bytes: PEAK.load(Relaxed).saturating_sub(current),
+        peak_resident_bytes: resident.then(resident::peak).flatten(),
+    };
+    (value, usage)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::alloc::{GlobalAlloc, Layout};
+
+    use super::{measure, resident, Bytes, Counting, Usage};
+
+    #[test]
+    fn test_measure() {
+        let ((), usage) = measure(|| unsafe {
+            let small = Layout::from_size_align(1024, 8).unwrap();
+            let ptr = Counting.alloc(small);
+            let ptr = Counting.realloc(ptr, small, 4096);
+            Counting.dealloc(ptr, Layout::from_size_align(4096, 8).unwrap());
+
+            let zeroed = Counting.alloc_zeroed(small);
+            Counting.dealloc(zeroed, small);
+        });
+
+        // Other tests may allocate concurrently when the counting allocator is installed
+        assert!(usage.allocations >= 3, "{:?}", usage);
+        assert!(usage.bytes >= 1024 + 4096 + 1024, "{:?}", usage);
+        assert!(usage.peak_heap_bytes >= 4096, "{:?}", usage);
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!("512B", Bytes(512).to_string());
+        assert_eq!("1.5KiB", Bytes(1536).to_string());
+        assert_eq!("3.0MiB", Bytes(3 << 20).to_string());
+
+        let mut usage = Usage {
+            allocations: 12,
+            bytes: 2048,
+            peak_heap_bytes: 100,
+            peak_resident_bytes: None,
+        };
+        assert_eq!(
+            "allocs       12  bytes    2.0KiB  heap peak      100B  resident peak         -",
+            usage.to_string()
+        );
+        usage.peak_resident_bytes = Some(8 << 20);
+        assert!(usage.to_string().ends_with("resident peak    8.0MiB"));
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_resident() {
+        // Some sandboxes do not let processes reset their peak
+        let Some(before) = resident::reset_peak() else {
+            return;
+        };
+        let touched = vec![1u8; 32 << 20];
+        let peak = resident::peak().unwrap();
+        drop(touched);
+
+        // Other tests may release memory concurrently, though not that much
+        assert!(peak >= before + (16 << 20), "{} then {}", before, peak);
+    }
+}
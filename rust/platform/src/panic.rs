@@ -0,0 +1,170 @@
+//! Catching of panics raised by challenges, so that a failing stage can be reported like any
+//! other error.
+
+use std::{
+    any::Any,
+    cell::{Cell, RefCell},
+    fmt,
+    panic::{self, AssertUnwindSafe},
+};
+
+thread_local! {
+    static LOCATION: RefCell<Option<String>> = const { RefCell::new(None) };
+    /// Number of nested [`catch`] running on this thread
+    static CATCHING: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Wraps the current panic hook so that panics raised within [`catch`] only record where they
+/// happened, [`catch`] reporting them. Those are traced at debug level, while any other panic,
+/// such as one of the platform itself, is handed to the previous hook.
+pub(crate) fn install_hook() {
+    let previous = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        if CATCHING.with(Cell::get) == 0 {
+            return previous(info);
+        }
+        let location = info.location().map(|l| l.to_string());
+        LOCATION.with(|l| *l.borrow_mut() = location);
+        tracing::debug!("{}", info);
+    }));
+}
+
+/// Panic caught while running a challenge
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Panic {
+    pub message: String,
+    pub location: Option<String>,
+}
+
+impl Panic {
+    fn from_payload(payload: Box<dyn Any + Send>) -> Self {
+        let message = if let Some(s) = payload.downcast_ref::<&str>() {
+            s.to_string()
+        } else if let Some(s) = payload.downcast_ref::<String>() {
+            s.clone()
+        } else {
+            "Box<dyn Any>".to_owned()
+        };
+        let location = LOCATION.with(|l| l.borrow_mut().take());
+        Self { message, location }
+    }
+
+    /// Whether the panic was raised by `todo!()` or `unimplemented!()`, whose messages are
+    /// `not yet implemented` and `not implemented`, followed by `: ` and the arguments if any
+    pub fn is_unimplemented(&self) -> bool {
+        ["not yet implemented", "not implemented"]
+            .iter()
+            .any(|payload| {
+                self.message
+                    .strip_prefix(payload)
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with(": "))
+            })
+    }
+}
+
+impl fmt::Display for Panic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.location {
+            Some(location) => write!(f, "panicked at {}: {}", location, self.message),
+            None => write!(f, "panicked: {}", self.message),
+        }
+    }
+}
+
+impl std::error::Error for Panic {}
+
+/// Runs `f`, catching any panic it raises
+pub(crate) fn catch<T>(f: impl FnOnce() -> T) -> Result<T, Panic> {
+    CATCHING.with(|c| c.set(c.get() + 1));
+    let res = panic::catch_unwind(AssertUnwindSafe(f));
+    CATCHING.with(|c| c.set(c.get() - 1));
+    res.map_err(Panic::from_payload)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        panic,
+        sync::{Arc, Mutex, MutexGuard, PoisonError},
+        thread,
+    };
+
+    use super::{catch, install_hook};
+
+    /// Held by the tests which panic or replace the panic hook, so that they run one at a time
+    fn serial() -> MutexGuard<'static, ()> {
+        static HOOK: Mutex<()> = Mutex::new(());
+        HOOK.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    #[test]
+    fn test_catch() {
+        let _serial = serial();
+        assert_eq!(Ok(3), catch(|| 3));
+
+        let panic = catch(|| panic!("Out of {}", "bounds")).unwrap_err();
+        assert_eq!("Out of bounds", panic.message);
+        assert!(!panic.is_unimplemented());
+    }
+
+    #[test]
+    fn test_unimplemented() {
+        let _serial = serial();
+        assert!(catch(|| todo!()).unwrap_err().is_unimplemented());
+        assert!(catch(|| todo!("stage2")).unwrap_err().is_unimplemented());
+        assert!(catch(|| unimplemented!()).unwrap_err().is_unimplemented());
+        assert!(catch(|| unimplemented!("day {}", 25))
+            .unwrap_err()
+            .is_unimplemented());
+
+        assert!(!catch(|| panic!("not implemented yet"))
+            .unwrap_err()
+            .is_unimplemented());
+        assert!(!catch(|| panic!("not yet implementedness"))
+            .unwrap_err()
+            .is_unimplemented());
+    }
+
+    #[test]
+    fn test_hook() {
+        // Only the panics of the threads below are recorded, those of the tests running
+        // concurrently being handed to the default hook as usual
+        let _serial = serial();
+        let reported = Arc::new(Mutex::new(Vec::new()));
+        let recorded = reported.clone();
+        let default = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            let thread = thread::current();
+            match thread.name().filter(|n| n.starts_with("hook-")) {
+                Some(name) => recorded.lock().unwrap().push(name.to_owned()),
+                None => default(info),
+            }
+        }));
+        install_hook();
+
+        let spawn = |name: &str, f: fn()| {
+            thread::Builder::new()
+                .name(name.to_owned())
+                .spawn(f)
+                .unwrap()
+                .join()
+        };
+        assert!(spawn("hook-caught", || {
+            let panic = catch(|| panic!("Caught")).unwrap_err();
+            assert!(panic.location.unwrap().contains("panic.rs"));
+        })
+        .is_ok());
+        assert!(spawn("hook-uncaught", || panic!("Not caught")).is_err());
+        assert!(spawn("hook-after", || {
+            let _ = catch(|| panic!("Caught"));
+            panic!("Not caught either");
+        })
+        .is_err());
+        drop(panic::take_hook());
+
+        assert_eq!(
+            vec!["hook-uncaught", "hook-after"],
+            *reported.lock().unwrap()
+        );
+    }
+}
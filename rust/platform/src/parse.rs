@@ -0,0 +1,611 @@
+//! Building blocks for parsing puzzle inputs.
+//!
+//! Inputs are handled as [`Text`], pieces of the data file remembering where they lie, so that
+//! every [`ParseError`] tells the lines and columns at which parsing failed, even once the input
+//! is split into blocks, lines and words. The runners print the offending part of the input
+//! along with the error.
+//!
+//! Positions are relative to the string given to [`Text::new`], which should be the whole data
+//! file:
+//!
+//! ```
+//! use platform::parse::{self, Pattern, Text};
+//!
+//! const VENT: Pattern = Pattern::new("{},{} -> {},{}");
+//!
+//! let vents = Text::new("0,9 -> 5,9\n8,0 -> 0,8\n")
+//!     .lines()
+//!     .map(|line| VENT.parse::<u32, 4>(line))
+//!     .collect::<Result<Vec<_>, _>>()
+//!     .unwrap();
+//! assert_eq!([8, 0, 0, 8], vents[1]);
+//!
+//! let err = parse::numbers::<u16>("199 200\n208 2l0\n").unwrap_err();
+//! assert_eq!(
+//!     "line 2, column 5: Invalid value \"2l0\": invalid digit found in string",
+//!     err.to_string()
+//! );
+//! ```
+
+use std::{fmt, str::FromStr};
+
+/// Location in a data file, both starting at 1. Columns count characters, not bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Position {
+    /// Position of the byte at `offset` in `source`
+    fn at(source: &str, offset: usize) -> Self {
+        let before = &source[..offset];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        Self {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+        }
+    }
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)
+    }
+}
+
+/// Range of a data file, from the first character covered to the one following the last
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub start: Position,
+    pub end: Position,
+}
+
+/// Invalid input, located in the data file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub span: Span,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.span.start, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl ParseError {
+    /// Line of `source` at which the error starts, with its span underlined by carets. Spans
+    /// covering several lines are underlined up to the end of their first line.
+    ///
+    /// `None` when the error does not point into `source`, such as an error from a [`Text`] which
+    /// was not created out of it.
+    pub fn snippet(&self, source: &str) -> Option<String> {
+        let Span { start, end } = self.span;
+        let line = source.lines().nth(start.line - 1)?;
+        let width = line.chars().count();
+        if start.column > width + 1 {
+            return None;
+        }
+        let last = if end.line == start.line {
+            end.column
+        } else {
+            width + 1
+        };
+        // Tabs are kept so that the carets line up with the characters above them
+        let padding: String = line
+            .chars()
+            .take(start.column - 1)
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        let gutter = start.line.to_string().len();
+        Some(format!(
+            "{:gutter$} |\n{} | {}\n{:gutter$} | {}{}",
+            "",
+            start.line,
+            line,
+            "",
+            padding,
+            "^".repeat(last.saturating_sub(start.column).max(1)),
+            gutter = gutter
+        ))
+    }
+
+    /// First parse error in the chain of causes of `err`
+    pub(crate) fn find(err: &anyhow::Error) -> Option<&Self> {
+        err.chain().find_map(|cause| cause.downcast_ref())
+    }
+}
+
+/// Piece of a data file, trimmed of its surrounding whitespace by most operations
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Text<'a> {
+    source: &'a str,
+    start: usize,
+    end: usize,
+}
+
+impl<'a> From<&'a str> for Text<'a> {
+    fn from(source: &'a str) -> Self {
+        Self::new(source)
+    }
+}
+
+impl fmt::Display for Text<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl<'a> Text<'a> {
+    /// Whole data file
+    pub fn new(source: &'a str) -> Self {
+        Self {
+            source,
+            start: 0,
+            end: source.len(),
+        }
+    }
+
+    pub fn as_str(&self) -> &'a str {
+        &self.source[self.start..self.end]
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    /// Position of the start of the text in the data file
+    pub fn position(&self) -> Position {
+        Position::at(self.source, self.start)
+    }
+
+    /// Range of the data file covered by the text
+    pub fn span(&self) -> Span {
+        Span {
+            start: self.position(),
+            end: Position::at(self.source, self.end),
+        }
+    }
+
+    /// Error spanning the text
+    pub fn error(&self, message: impl fmt::Display) -> ParseError {
+        ParseError {
+            span: self.span(),
+            message: message.to_string(),
+        }
+    }
+
+    /// Text of `part`, which must be a slice of [`Text::as_str`]
+    fn slice(&self, part: &'a str) -> Self {
+        let offset = part.as_ptr() as usize - self.as_str().as_ptr() as usize;
+        debug_assert!(offset + part.len() <= self.end - self.start);
+        Self {
+            source: self.source,
+            start: self.start + offset,
+            end: self.start + offset + part.len(),
+        }
+    }
+
+    pub fn trim(self) -> Self {
+        self.slice(self.as_str().trim())
+    }
+
+    /// Non-blank lines, trimmed
+    pub fn lines(self) -> impl Iterator<Item = Text<'a>> {
+        self.as_str()
+            .lines()
+            .map(move |line| self.slice(line).trim())
+            .filter(|line| !line.is_empty())
+    }
+
+    /// Groups of lines separated by blank lines, trimmed
+    pub fn blocks(self) -> impl Iterator<Item = Text<'a>> {
+        let mut blocks = Vec::new();
+        let mut current = None::<(usize, usize)>;
+        let mut offset = self.start;
+        for line in self.as_str().split_inclusive('\n') {
+            let end = offset + line.len();
+            if line.trim().is_empty() {
+                blocks.extend(current.take());
+            } else {
+                current = Some((current.map_or(offset, |(start, _)| start), end));
+            }
+            offset = end;
+        }
+        blocks.extend(current);
+        blocks.into_iter().map(move |(start, end)| {
+            Self {
+                source: self.source,
+                start,
+                end,
+            }
+            .trim()
+        })
+    }
+
+    /// Pieces separated by whitespace
+    pub fn words(self) -> impl Iterator<Item = Text<'a>> {
+        self.as_str()
+            .split_whitespace()
+            .map(move |word| self.slice(word))
+    }
+
+    /// Pieces separated by `separator`, trimmed
+    pub fn split(self, separator: char) -> impl Iterator<Item = Text<'a>> {
+        self.as_str()
+            .split(separator)
+            .map(move |part| self.slice(part).trim())
+    }
+
+    /// Characters with their own text, for locating errors on a single cell
+    fn chars(self) -> impl Iterator<Item = (char, Text<'a>)> {
+        let text = self.as_str();
+        text.char_indices()
+            .map(move |(i, c)| (c, self.slice(&text[i..i + c.len_utf8()])))
+    }
+
+    pub fn parse<T>(self) -> Result<T, ParseError>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        self.as_str()
+            .parse()
+            .map_err(|err| self.error(format_args!("Invalid value {:?}: {}", self.as_str(), err)))
+    }
+}
+
+/// Numbers separated by whitespace, such as one number per line
+pub fn numbers<'a, T>(text: impl Into<Text<'a>>) -> Result<Vec<T>, ParseError>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    text.into().words().map(Text::parse).collect()
+}
+
+/// Values separated by commas on a single line, such as `7,4,9,5`
+pub fn comma_list<'a, T>(text: impl Into<Text<'a>>) -> Result<Vec<T>, ParseError>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    text.into().split(',').map(Text::parse).collect()
+}
+
+/// Each non-blank line parsed as a value
+pub fn lines<'a, T>(text: impl Into<Text<'a>>) -> Result<Vec<T>, ParseError>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    text.into().lines().map(Text::parse).collect()
+}
+
+/// Rows of a grid made of one row per line, all of them as wide as the first one
+fn rows<'a, T>(
+    text: Text<'a>,
+    mut row: impl FnMut(Text<'a>) -> Result<Vec<T>, ParseError>,
+) -> Result<Vec<Vec<T>>, ParseError> {
+    let mut rows = Vec::<Vec<T>>::new();
+    for line in text.lines() {
+        let cells = row(line)?;
+        if let Some(first) = rows.first() {
+            if cells.len() != first.len() {
+                return Err(line.error(format_args!(
+                    "Row has {} cells, expected {}",
+                    cells.len(),
+                    first.len()
+                )));
+            }
+        }
+        rows.push(cells);
+    }
+    if rows.is_empty() {
+        return Err(text.error("Grid is empty"));
+    }
+    Ok(rows)
+}
+
+/// Rows of a grid with one character per cell, converted by `cell`
+pub fn grid<'a, T, E: fmt::Display>(
+    text: impl Into<Text<'a>>,
+    mut cell: impl FnMut(char) -> Result<T, E>,
+) -> Result<Vec<Vec<T>>, ParseError> {
+    rows(text.into(), |line| {
+        line.chars()
+            .map(|(c, at)| cell(c).map_err(|err| at.error(err)))
+            .collect()
+    })
+}
+
+/// Rows of a grid of characters, such as `#` and `.`
+pub fn char_grid<'a, T>(text: impl Into<Text<'a>>) -> Result<Vec<Vec<T>>, ParseError>
+where
+    T: TryFrom<char>,
+    T::Error: fmt::Display,
+{
+    grid(text, T::try_from)
+}
+
+/// Rows of a grid of digits, such as a height map
+pub fn digit_grid<'a>(text: impl Into<Text<'a>>) -> Result<Vec<Vec<u8>>, ParseError> {
+    grid(text, |c| {
+        c.to_digit(10)
+            .map(|digit| digit as u8)
+            .ok_or_else(|| format!("Invalid digit {:?}", c))
+    })
+}
+
+/// Rows of a grid of numbers separated by whitespace, such as a bingo board
+pub fn number_grid<'a, T>(text: impl Into<Text<'a>>) -> Result<Vec<Vec<T>>, ParseError>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    rows(text.into(), numbers)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Piece<'p> {
+    Field,
+    Literal(&'p str),
+    Space,
+}
+
+/// Layout of a record, in which `{}` stands for a field such as in `"{},{} -> {},{}"`.
+///
+/// Whitespace in the layout matches any amount of whitespace. A field spans up to the literal
+/// following it, or up to the next whitespace when followed by whitespace, and is trimmed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pattern(&'static str);
+
+impl Pattern {
+    pub const fn new(layout: &'static str) -> Self {
+        Self(layout)
+    }
+
+    fn pieces(&self) -> Vec<Piece<'static>> {
+        let mut pieces = Vec::new();
+        for (i, literal) in self.0.split("{}").enumerate() {
+            if i > 0 {
+                pieces.push(Piece::Field);
+            }
+            let mut rest = literal;
+            while !rest.is_empty() {
+                let trimmed = rest.trim_start();
+                if trimmed.len() < rest.len() {
+                    pieces.push(Piece::Space);
+                }
+                let end = trimmed.find(char::is_whitespace).unwrap_or(trimmed.len());
+                if end > 0 {
+                    pieces.push(Piece::Literal(&trimmed[..end]));
+                }
+                rest = &trimmed[end..];
+            }
+        }
+        pieces
+    }
+
+    /// Fields of `text`, failing unless it follows the layout
+    pub fn fields<'a>(&self, text: impl Into<Text<'a>>) -> Result<Vec<Text<'a>>, ParseError> {
+        let text = text.into().trim();
+        let pieces = self.pieces();
+        let mut fields = Vec::new();
+        let mut rest = text;
+        for (i, piece) in pieces.iter().enumerate() {
+            let s = rest.as_str();
+            let consumed = match *piece {
+                Piece::Space => s.len() - s.trim_start().len(),
+                Piece::Literal(literal) if s.starts_with(literal) => literal.len(),
+                Piece::Literal(literal) => {
+                    return Err(rest.error(format_args!("Expected {:?}", literal)))
+                }
+                Piece::Field => {
+                    let end = match pieces.get(i + 1) {
+                        Some(Piece::Literal(literal)) => s.find(literal),
+                        Some(Piece::Space) => s.find(char::is_whitespace),
+                        _ => None,
+                    }
+                    .unwrap_or(s.len());
+                    let field = rest.slice(&s[..end]).trim();
+                    if field.is_empty() {
+                        return Err(rest.error("Missing field"));
+                    }
+                    fields.push(field);
+                    end
+                }
+            };
+            rest = rest.slice(&s[consumed..]);
+        }
+        let rest = rest.trim();
+        if !rest.is_empty() {
+            return Err(rest.error(format_args!("Unexpected {:?}", rest.as_str())));
+        }
+        Ok(fields)
+    }
+
+    /// Fields of `text` parsed as values of the same type, of which the layout has exactly `N`
+    pub fn parse<'a, T, const N: usize>(
+        &self,
+        text: impl Into<Text<'a>>,
+    ) -> Result<[T; N], ParseError>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        let fields = self.fields(text)?;
+        assert_eq!(
+            N,
+            fields.len(),
+            "Pattern {:?} has {} fields",
+            self.0,
+            fields.len()
+        );
+        let values = fields
+            .into_iter()
+            .map(Text::parse)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(values.try_into().ok().unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        comma_list, digit_grid, number_grid, numbers, ParseError, Pattern, Position, Text,
+    };
+
+    fn position(err: ParseError) -> (usize, usize) {
+        (err.span.start.line, err.span.start.column)
+    }
+
+    #[test]
+    fn test_position() {
+        let source = "ab\ncdé\n\nf";
+        assert_eq!(Position { line: 1, column: 1 }, Position::at(source, 0));
+        assert_eq!(Position { line: 2, column: 3 }, Position::at(source, 5));
+        assert_eq!(Position { line: 2, column: 4 }, Position::at(source, 7));
+        assert_eq!(Position { line: 4, column: 1 }, Position::at(source, 9));
+    }
+
+    #[test]
+    fn test_blocks() {
+        let text = Text::new("7,4,9\n\n 1 2\n3 4\n  \n\n5 6\n7 8\n\n");
+        let blocks: Vec<_> = text.blocks().collect();
+        assert_eq!(
+            vec!["7,4,9", "1 2\n3 4", "5 6\n7 8"],
+            blocks.iter().map(Text::as_str).collect::<Vec<_>>()
+        );
+        assert_eq!(Position { line: 3, column: 2 }, blocks[1].position());
+        assert_eq!(
+            vec![(4, 1), (4, 3)],
+            blocks[1]
+                .lines()
+                .nth(1)
+                .unwrap()
+                .words()
+                .map(|w| (w.position().line, w.position().column))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_lists() {
+        assert_eq!(
+            vec![199, 200, 208],
+            numbers::<u16>("199\n200\r\n208\n").unwrap()
+        );
+        assert_eq!((2, 3), position(numbers::<u16>("1\n2 x").unwrap_err()));
+        assert_eq!(vec![7, 4, 9], comma_list::<u32>(" 7, 4,9").unwrap());
+        let err = comma_list::<u32>("7,4,,9").unwrap_err();
+        assert_eq!(
+            "line 1, column 5: Invalid value \"\": cannot parse integer from empty string",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn test_grids() {
+        assert_eq!(
+            vec![vec![2, 1, 9], vec![3, 9, 8]],
+            digit_grid("219\n398\n").unwrap()
+        );
+        let err = digit_grid("219\n3x8\n").unwrap_err();
+        assert_eq!("line 2, column 2: Invalid digit 'x'", err.to_string());
+        let err = digit_grid("219\n39\n").unwrap_err();
+        assert_eq!(
+            "line 2, column 1: Row has 2 cells, expected 3",
+            err.to_string()
+        );
+        assert_eq!((1, 1), position(digit_grid("\n").unwrap_err()));
+
+        let text = Text::new("draws\n\n22 13\n 8  2\n");
+        let board = text.blocks().nth(1).unwrap();
+        assert_eq!(
+            vec![vec![22, 13], vec![8, 2]],
+            number_grid::<u32>(board).unwrap()
+        );
+        let err = number_grid::<u32>(Text::new("1 2\n3 -4\n")).unwrap_err();
+        assert_eq!((2, 3), position(err));
+    }
+
+    #[test]
+    fn test_pattern() {
+        const VENT: Pattern = Pattern::new("{},{} -> {},{}");
+        assert_eq!([0, 9, 5, 9], VENT.parse::<u32, 4>("0,9 -> 5,9").unwrap());
+        assert_eq!([0, 9, 5, 9], VENT.parse::<u32, 4>(" 0,9   ->5,9 ").unwrap());
+        assert_eq!(
+            (1, 5),
+            position(VENT.parse::<u32, 4>("0,9 => 5,9").unwrap_err())
+        );
+        assert_eq!(
+            (1, 3),
+            position(VENT.parse::<u32, 4>("0,,9 -> 5,9").unwrap_err())
+        );
+        let err = VENT.parse::<u32, 4>("0,9 -> 5,9 -> 1,2").unwrap_err();
+        assert_eq!(
+            "line 1, column 10: Invalid value \"9 -> 1,2\": invalid digit found in string",
+            err.to_string()
+        );
+
+        const COMMAND: Pattern = Pattern::new("{} {}");
+        let fields = COMMAND.fields("forward 5").unwrap();
+        assert_eq!(
+            vec!["forward", "5"],
+            fields.iter().map(Text::as_str).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            "line 1, column 8: Missing field",
+            COMMAND.fields("forward").unwrap_err().to_string()
+        );
+        assert_eq!(
+            "line 1, column 5: Unexpected \"x\"",
+            Pattern::new("({})")
+                .fields("(5) x")
+                .unwrap_err()
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn test_snippet() {
+        const VENT: Pattern = Pattern::new("{},{} -> {},{}");
+        let data = "0,9 -> 5,9\n8,0 -> 0,8\n9,4 -> 3,4\n2,2 -> 2,1\n7,0 -> 7,4\n6,4 -> 2,0\n\
+                    0,9 -> 2,9\n3,4 -> 1,4\n0,0 -> 8,8\n5,5 => 8,2\n";
+        let err = Text::new(data)
+            .lines()
+            .map(|line| VENT.parse::<u32, 4>(line))
+            .find_map(Result::err)
+            .unwrap();
+        assert_eq!(
+            "   |\n10 | 5,5 => 8,2\n   |     ^^^^^^",
+            err.snippet(data).unwrap()
+        );
+
+        let data = "1,2\n\t3,4000000000000\n";
+        let err = comma_list::<u32>(Text::new(data).lines().nth(1).unwrap()).unwrap_err();
+        assert_eq!(
+            "  |\n2 | \t3,4000000000000\n  | \t  ^^^^^^^^^^^^^",
+            err.snippet(data).unwrap()
+        );
+
+        // Errors at the end of a line or spanning several lines still get a caret
+        let err = Pattern::new("{} {}").fields("forward").unwrap_err();
+        assert_eq!(
+            "  |\n1 | forward\n  |        ^",
+            err.snippet("forward").unwrap()
+        );
+        let err = number_grid::<u32>("1 2\n3\n").unwrap_err();
+        assert_eq!("  |\n2 | 3\n  | ^", err.snippet("1 2\n3\n").unwrap());
+        let err = Text::new("ab\ncd").error("Unexpected");
+        assert_eq!("  |\n1 | ab\n  | ^^", err.snippet("ab\ncd").unwrap());
+
+        assert_eq!(None, err.snippet(""));
+        let found = anyhow::Error::new(err.clone()).context("Parsing board 1");
+        assert_eq!(Some(&err), ParseError::find(&found));
+    }
+}
@@ -0,0 +1,253 @@
+use std::{any::Any, collections::BTreeMap, fmt::Debug, sync::Arc};
+
+use crate::{Answer, Challenge, CustomStage, Stage};
+
+/// Type-erased [`Challenge::Input`], shared between the stages of a challenge
+pub(crate) type Input = Arc<dyn Any + Send + Sync>;
+
+/// Object-safe view over a [`Challenge`], allowing challenges of different types to be stored
+/// side by side.
+pub(crate) trait Entry: Send + Sync {
+    fn day(&self) -> u8;
+    fn name(&self) -> String;
+    fn custom_stages(&self) -> &'static [CustomStage];
+    fn parse(&self, data: &str) -> anyhow::Result<Input>;
+    /// Runs a stage over an input produced by [`Entry::parse`] of the same entry
+    fn stage(&self, stage: Stage, input: &Input) -> anyhow::Result<Answer>;
+
+    /// Official stages followed by the custom ones
+    fn stages(&self) -> Vec<Stage> {
+        let custom = self.custom_stages().iter().map(|s| Stage::Custom(s.name));
+        Stage::ALL.into_iter().chain(custom).collect()
+    }
+
+    /// Official stage of which `stage` is a variant, `stage` itself when it is not a variant
+    fn official(&self, stage: Stage) -> Stage {
+        self.custom_stages()
+            .iter()
+            .find(|custom| Stage::Custom(custom.name) == stage)
+            .and_then(|custom| custom.variant_of)
+            .unwrap_or(stage)
+    }
+
+    /// Each of `stages` followed by its variants
+    fn with_variants(&self, stages: &[Stage]) -> Vec<Stage> {
+        let mut all = Vec::new();
+        for &stage in stages {
+            all.push(stage);
+            let variants = self
+                .custom_stages()
+                .iter()
+                .filter(|custom| custom.variant_of == Some(stage))
+                .map(|custom| Stage::Custom(custom.name));
+            for variant in variants {
+                if !stages.contains(&variant) && !all.contains(&variant) {
+                    all.push(variant);
+                }
+            }
+        }
+        all
+    }
+}
+
+impl<C: 'static + Challenge + Debug + Send + Sync> Entry for C {
+    fn day(&self) -> u8 {
+        C::DAY
+    }
+
+    fn name(&self) -> String {
+        format!("{:?}", self)
+    }
+
+    fn custom_stages(&self) -> &'static [CustomStage] {
+        C::CUSTOM_STAGES
+    }
+
+    fn parse(&self, data: &str) -> anyhow::Result<Input> {
+        Ok(Arc::new(Challenge::parse(self, data)?))
+    }
+
+    fn stage(&self, stage: Stage, input: &Input) -> anyhow::Result<Answer> {
+        let input = input
+            .downcast_ref::<C::Input>()
+            .expect("input parsed by another challenge");
+        match stage {
+            Stage::Stage1 => self.stage1(input),
+            Stage::Stage2 => self.stage2(input),
+            Stage::Custom(name) => self.custom(name, input),
+        }
+    }
+}
+
+/// Lists custom stages under their label for the `--help` output, `None` when there are none
+pub(crate) fn custom_stages_help<'a>(
+    stages: impl IntoIterator<Item = (String, &'a CustomStage)>,
+) -> Option<String> {
+    let stages: Vec<_> = stages.into_iter().collect();
+    let width = stages.iter().map(|(label, _)| label.len()).max()?;
+    let mut help = "CUSTOM STAGES:".to_owned();
+    for (label, stage) in stages {
+        help += &format!("\n    {:<width$}    {}", label, stage.about, width = width);
+        if let Some(official) = stage.variant_of {
+            help += &format!(" (variant of {})", official);
+        }
+    }
+    Some(help)
+}
+
+/// Collection of challenges, ordered by day
+#[derive(Default, Clone)]
+pub struct Registry {
+    challenges: BTreeMap<u8, Arc<dyn Entry>>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a challenge to the registry.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a challenge has already been registered for the same day.
+    pub fn register<C: 'static + Challenge + Debug + Send + Sync>(mut self, challenge: C) -> Self {
+        let previous = self.challenges.insert(C::DAY, Arc::new(challenge));
+        assert!(previous.is_none(), "Day {} registered twice", C::DAY);
+        self
+    }
+
+    pub fn days(&self) -> impl '_ + Iterator<Item = u8> {
+        self.challenges.keys().copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.challenges.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.challenges.is_empty()
+    }
+
+    pub(crate) fn get(&self, day: u8) -> Option<&Arc<dyn Entry>> {
+        self.challenges.get(&day)
+    }
+
+    /// Help listing the custom stages of every challenge
+    pub(crate) fn custom_stages_help(&self) -> Option<String> {
+        custom_stages_help(self.iter().flat_map(|challenge| {
+            let day = challenge.day();
+            challenge
+                .custom_stages()
+                .iter()
+                .map(move |stage| (format!("day{:02} {}", day, stage.name), stage))
+        }))
+    }
+
+    pub(crate) fn iter(&self) -> impl '_ + Iterator<Item = &Arc<dyn Entry>> {
+        self.challenges.values()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Registry;
+    use crate::{
+        exec::guard,
+        testing::{Fake, Plain},
+        Answer, Error, Stage,
+    };
+
+    #[test]
+    fn test_ordering() {
+        let registry = Registry::new().register(Fake::<7>).register(Fake::<2>);
+
+        assert_eq!(vec![2, 7], registry.days().collect::<Vec<_>>());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_duplicate_day() {
+        let _ = Registry::new().register(Fake::<1>).register(Fake::<1>);
+    }
+
+    #[test]
+    fn test_dispatch() {
+        let registry = Registry::new().register(Fake::<3>);
+        let entry = registry.get(3).unwrap();
+        let input = entry.parse(" abc\n").unwrap();
+
+        assert_eq!(
+            Answer::Integer(3),
+            entry.stage(Stage::Stage1, &input).unwrap()
+        );
+        assert_eq!(
+            Answer::from("abc"),
+            entry.stage(Stage::Stage2, &input).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_custom_stages() {
+        let registry = Registry::new().register(Plain).register(Fake::<9>);
+        let entry = registry.get(9).unwrap();
+        assert_eq!(
+            vec![
+                Stage::Stage1,
+                Stage::Stage2,
+                Stage::Custom("stats"),
+                Stage::Custom("stage1-naive")
+            ],
+            entry.stages()
+        );
+        assert_eq!(2, registry.get(4).unwrap().stages().len());
+
+        // A stage the challenge does not know fails, instead of counting as not implemented
+        let plain = registry.get(4).unwrap();
+        let input = plain.parse("").unwrap();
+        let err = guard(|| plain.stage(Stage::Custom("stats"), &input), Error::Solve).unwrap_err();
+        assert_eq!(
+            "stage failed: unknown custom stage stats",
+            format!("{:#}", err)
+        );
+
+        let input = entry.parse("a b c").unwrap();
+        assert_eq!(
+            Answer::Integer(3),
+            entry.stage(Stage::Custom("stats"), &input).unwrap()
+        );
+
+        assert_eq!(
+            Some(
+                "CUSTOM STAGES:\n    \
+                   day09 stats           Counts the words of the input\n    \
+                   day09 stage1-naive    Counts the characters one by one (variant of stage1)"
+            ),
+            registry.custom_stages_help().as_deref()
+        );
+        assert_eq!(None, Registry::new().register(Plain).custom_stages_help());
+    }
+
+    #[test]
+    fn test_variants() {
+        let registry = Registry::new().register(Fake::<9>);
+        let entry = registry.get(9).unwrap();
+        let naive = Stage::Custom("stage1-naive");
+
+        assert_eq!(Stage::Stage1, entry.official(naive));
+        assert_eq!(
+            Stage::Custom("stats"),
+            entry.official(Stage::Custom("stats"))
+        );
+        assert_eq!(Stage::Stage2, entry.official(Stage::Stage2));
+
+        assert_eq!(
+            vec![Stage::Stage2, Stage::Stage1, naive],
+            entry.with_variants(&[Stage::Stage2, Stage::Stage1])
+        );
+        assert_eq!(
+            vec![naive, Stage::Stage1],
+            entry.with_variants(&[naive, Stage::Stage1])
+        );
+    }
+}
@@ -0,0 +1,176 @@
+//! Printing of stage answers and of the timing summary of an invocation.
+
+use std::{fmt, ops::AddAssign, time::Duration};
+
+use crate::{error::ExitStatus, verify::Verdict, Answer};
+
+/// Prints the answer of a stage, along with its verdict when checking against expected answers
+pub(crate) fn print_answer(label: &str, answer: &Answer, verdict: Option<&Verdict>) {
+    match verdict {
+        Some(verdict) => println!("{}: {} [{}]", label, answer, verdict),
+        None => println!("{}: {}", label, answer),
+    }
+}
+
+/// Number of stages which did not produce a correct answer, by cause
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Tally {
+    pub failed: usize,
+    pub mismatched: usize,
+    pub unimplemented: usize,
+}
+
+impl Tally {
+    pub fn status(&self) -> ExitStatus {
+        if self.failed > 0 {
+            ExitStatus::Failure
+        } else if self.mismatched > 0 {
+            ExitStatus::Mismatch
+        } else if self.unimplemented > 0 {
+            ExitStatus::Unimplemented
+        } else {
+            ExitStatus::Success
+        }
+    }
+}
+
+impl AddAssign for Tally {
+    fn add_assign(&mut self, other: Self) {
+        self.failed += other.failed;
+        self.mismatched += other.mismatched;
+        self.unimplemented += other.unimplemented;
+    }
+}
+
+#[derive(Debug)]
+struct Row {
+    label: String,
+    elapsed: Option<Duration>,
+    status: String,
+}
+
+/// Timings and outcome of every phase run during an invocation
+#[derive(Debug, Default)]
+pub(crate) struct Summary {
+    rows: Vec<Row>,
+    wall: Option<Duration>,
+}
+
+impl Summary {
+    pub fn push(
+        &mut self,
+        label: impl Into<String>,
+        elapsed: Option<Duration>,
+        status: impl ToString,
+    ) {
+        self.rows.push(Row {
+            label: label.into(),
+            elapsed,
+            status: status.to_string(),
+        });
+    }
+
+    /// Records the wall-clock time of the invocation, shorter than the total when phases ran
+    /// concurrently
+    pub fn wall(&mut self, elapsed: Duration) {
+        self.wall = Some(elapsed);
+    }
+
+    pub fn total(&self) -> Duration {
+        self.rows.iter().filter_map(|r| r.elapsed).sum()
+    }
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self
+            .rows
+            .iter()
+            .map(|r| r.label.len())
+            .chain(["total".len()])
+            .max()
+            .unwrap_or_default();
+        writeln!(f, "Summary:")?;
+        for row in &self.rows {
+            let elapsed = row
+                .elapsed
+                .map(|d| format!("{:.3?}", d))
+                .unwrap_or_else(|| "-".into());
+            writeln!(
+                f,
+                "  {:<width$}  {:>10}  {}",
+                row.label,
+                elapsed,
+                row.status,
+                width = width
+            )?;
+        }
+        write!(
+            f,
+            "  {:<width$}  {:>10}",
+            "total",
+            format!("{:.3?}", self.total()),
+            width = width
+        )?;
+        if let Some(wall) = self.wall {
+            write!(
+                f,
+                "\n  {:<width$}  {:>10}",
+                "wall",
+                format!("{:.3?}", wall),
+                width = width
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{Summary, Tally};
+    use crate::error::ExitStatus;
+
+    #[test]
+    fn test_tally_status() {
+        let mut tally = Tally::default();
+        assert_eq!(ExitStatus::Success, tally.status());
+
+        tally += Tally {
+            unimplemented: 1,
+            ..Tally::default()
+        };
+        assert_eq!(ExitStatus::Unimplemented, tally.status());
+
+        tally += Tally {
+            mismatched: 2,
+            ..Tally::default()
+        };
+        assert_eq!(ExitStatus::Mismatch, tally.status());
+
+        tally.failed += 1;
+        assert_eq!(ExitStatus::Failure, tally.status());
+    }
+
+    #[test]
+    fn test_summary() {
+        let mut summary = Summary::default();
+        summary.push("parse", Some(Duration::from_micros(250)), "ok");
+        summary.push("stage1", Some(Duration::from_micros(750)), "pass");
+        summary.push("stage2", None, "error");
+
+        assert_eq!(Duration::from_millis(1), summary.total());
+        assert_eq!(
+            "Summary:\n  \
+               parse    250.000µs  ok\n  \
+               stage1   750.000µs  pass\n  \
+               stage2           -  error\n  \
+               total      1.000ms",
+            summary.to_string()
+        );
+
+        summary.wall(Duration::from_micros(600));
+        assert!(summary.to_string().ends_with("\n  wall     600.000µs"));
+    }
+}
@@ -0,0 +1,405 @@
+//! Command line front-end running any challenge of a [`Registry`].
+
+use std::{path::Path, process::ExitCode, sync::Arc, time::Instant};
+
+use anyhow::Context;
+
+use structopt::StructOpt;
+
+use crate::{
+    bench::BenchOptions,
+    cache::CacheOptions,
+    client::{Client, Submission},
+    exec::{exec_bench, run_stages, Outcome, RuntimeOptions},
+    logging::LogOptions,
+    panic,
+    registry::Entry,
+    report::{Summary, Tally},
+    verify::VerifyOptions,
+    Answer, Error, ExitStatus, Registry, Source, Stage, StageSelection,
+};
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "aoc", about = "Runs the Advent of Code challenges")]
+struct Cli {
+    #[structopt(flatten)]
+    log: LogOptions,
+    #[structopt(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, StructOpt)]
+enum Command {
+    /// Run the challenges of one or every day
+    Run(RunOptions),
+    /// List the registered challenges
+    List,
+    /// Download the inputs of one or every day into `inputs/`, using the session token of
+    /// `AOC_SESSION`
+    Fetch {
+        /// Day to download
+        #[structopt(short, long, required_unless = "all")]
+        day: Option<u8>,
+        /// Download the input of every registered day
+        #[structopt(long, conflicts_with = "day")]
+        all: bool,
+    },
+    /// Submit the answer to a stage, using the session token of `AOC_SESSION`
+    Submit {
+        /// Day of the answer
+        #[structopt(short, long)]
+        day: u8,
+        /// Stage of the answer
+        #[structopt(short, long)]
+        stage: Stage,
+        answer: Answer,
+    },
+}
+
+#[derive(Debug, StructOpt)]
+struct RunOptions {
+    /// Day to run
+    #[structopt(short, long, required_unless = "all")]
+    day: Option<u8>,
+    /// Run every registered day, skipping the days whose input cannot be found
+    #[structopt(long, conflicts_with_all = &["day", "data file"])]
+    all: bool,
+    /// Stages to run: a stage name, a comma-separated list of stages, or `all` for `stage1` and
+    /// `stage2`
+    #[structopt(short, long, default_value = "all")]
+    stage: StageSelection,
+    /// Also run the variants of the selected stages, failing unless they give the same answers.
+    /// The cache is bypassed so that the time of every variant is reported
+    #[structopt(long)]
+    cross_check: bool,
+    /// File pointing to the challenge data file, `-` reading from stdin. When omitted, the file
+    /// of each day is looked up in `inputs/dayNN.txt`, then in `dayNN/data.txt`
+    #[structopt(name = "data file")]
+    filename: Option<Source>,
+    #[structopt(flatten)]
+    verify: VerifyOptions,
+    #[structopt(flatten)]
+    bench: BenchOptions,
+    #[structopt(flatten)]
+    runtime: RuntimeOptions,
+    #[structopt(flatten)]
+    cache: CacheOptions,
+}
+
+/// Challenge to run, along with its stages to run
+type Selected = (Arc<dyn Entry>, Vec<Stage>);
+
+impl RunOptions {
+    /// Challenges to run out of `registry`
+    fn select(&self, registry: &Registry) -> anyhow::Result<Vec<Selected>> {
+        let challenges: Vec<Arc<dyn Entry>> = match self.day {
+            Some(day) if !self.all => vec![registry
+                .get(day)
+                .ok_or_else(|| anyhow::anyhow!("No challenge registered for day {}", day))?
+                .clone()],
+            _ => registry.iter().cloned().collect(),
+        };
+        challenges
+            .into_iter()
+            .map(|challenge| {
+                let stages = self
+                    .stage
+                    .resolve(&challenge.stages())
+                    .with_context(|| format!("Selecting the stages of day {}", challenge.day()))?;
+                let stages = if self.cross_check {
+                    challenge.with_variants(&stages)
+                } else {
+                    stages
+                };
+                Ok((challenge, stages))
+            })
+            .collect()
+    }
+}
+
+fn data_source(options: &RunOptions, day: u8) -> anyhow::Result<Source> {
+    data_source_in(options, Path::new("."), day)
+}
+
+fn data_source_in(options: &RunOptions, dir: &Path, day: u8) -> anyhow::Result<Source> {
+    match &options.filename {
+        Some(source) => Ok(source.clone()),
+        None => Source::locate_in(dir, day),
+    }
+}
+
+/// Entry point of the all-days runner: parses the command line and runs the selected challenges
+/// out of `registry`. Stages which are not implemented yet are skipped, see [`ExitStatus`] for the
+/// exit code.
+pub fn run(registry: Registry) -> ExitCode {
+    let help = registry.custom_stages_help();
+    let app = Cli::clap();
+    let cli = match &help {
+        Some(help) => Cli::from_clap(&app.after_help(help.as_str()).get_matches()),
+        None => Cli::from_clap(&app.get_matches()),
+    };
+    let status = cli.log.init().and_then(|_trace| {
+        panic::install_hook();
+        match cli.command {
+            Command::List => {
+                for challenge in registry.iter() {
+                    let stages: Vec<_> = challenge.stages().iter().map(Stage::to_string).collect();
+                    println!(
+                        "day{:02}\t{}\t{}",
+                        challenge.day(),
+                        challenge.name(),
+                        stages.join(",")
+                    );
+                }
+                Ok(ExitStatus::Success)
+            }
+            Command::Run(options) => run_challenges(&registry, options),
+            Command::Fetch { day, all } => {
+                let client = Client::from_env()?;
+                let days = match day {
+                    Some(day) if !all => vec![day],
+                    _ => registry.days().collect(),
+                };
+                for day in days {
+                    client.input(day)?;
+                    println!("day{:02}: {}", day, client.cache_path(day).display());
+                }
+                Ok(ExitStatus::Success)
+            }
+            Command::Submit { day, stage, answer } => {
+                let submission = Client::from_env()?.submit(day, stage, &answer)?;
+                println!("day{:02} {}: {}", day, stage, submission);
+                Ok(match submission {
+                    Submission::Correct | Submission::AlreadySolved => ExitStatus::Success,
+                    Submission::Incorrect { .. } => ExitStatus::Mismatch,
+                    Submission::RateLimited { .. } => ExitStatus::Failure,
+                })
+            }
+        }
+    });
+    ExitStatus::report(status)
+}
+
+fn run_challenges(registry: &Registry, options: RunOptions) -> anyhow::Result<ExitStatus> {
+    let challenges = options.select(registry)?;
+    let num_runs: usize = challenges.iter().map(|(_, stages)| stages.len()).sum();
+
+    let answers = options.verify.load()?;
+
+    let rt = options.runtime.runtime()?;
+    if let Some(iterations) = options.bench.bench {
+        let reports = rt.block_on(async {
+            let limits = options.runtime.limits();
+            let mut reports = Vec::new();
+            for (challenge, stages) in challenges {
+                let source = match data_source(&options, challenge.day()) {
+                    Ok(source) => source,
+                    Err(err) if options.all => {
+                        eprintln!("day{:02} skipped: {:#}", challenge.day(), err);
+                        continue;
+                    }
+                    Err(err) => return Err(err),
+                };
+                for stage in stages {
+                    let label = format!("day{:02} {}", challenge.day(), stage);
+                    let report = match exec_bench(
+                        challenge.clone(),
+                        stage,
+                        source.clone(),
+                        iterations,
+                        &limits,
+                    )
+                    .await
+                    {
+                        Ok(report) => report,
+                        Err(err) if matches!(err.downcast_ref(), Some(Error::Unimplemented)) => {
+                            eprintln!("{}: not implemented, skipped", label);
+                            continue;
+                        }
+                        Err(err) => return Err(err.context(format!("Benchmarking {}", label))),
+                    };
+                    println!("{}", report);
+                    reports.push(report);
+                }
+            }
+            Ok::<_, anyhow::Error>(reports)
+        });
+        rt.shutdown_background();
+        options.bench.write_json(&reports?)?;
+        return Ok(ExitStatus::Success);
+    }
+
+    let start = Instant::now();
+    let cache = options.cache.open().filter(|_| !options.cross_check);
+    let outcomes = rt.block_on(async {
+        let limits = options.runtime.limits();
+        let all = options.all;
+        let tasks: Vec<_> = challenges
+            .into_iter()
+            .map(|(challenge, stages)| {
+                let source = data_source(&options, challenge.day());
+                let num_stages = stages.len();
+                let (entry, limits, cache) = (challenge.clone(), limits.clone(), cache.clone());
+                let task = tokio::spawn(async move {
+                    match source {
+                        Ok(source) => {
+                            run_stages(challenge, source, &stages, &limits, cache.as_deref()).await
+                        }
+                        Err(error) if all => Outcome::Missing { error },
+                        Err(error) => Outcome::Failed {
+                            phase: "read",
+                            error,
+                            snippet: None,
+                        },
+                    }
+                });
+                (entry, num_stages, task)
+            })
+            .collect();
+        let mut outcomes = Vec::with_capacity(tasks.len());
+        for (challenge, num_stages, task) in tasks {
+            outcomes.push((challenge, num_stages, task.await?));
+        }
+        Ok::<_, anyhow::Error>(outcomes)
+    });
+    // Stages which timed out may still be running, they are abandoned instead of waited for
+    rt.shutdown_background();
+    let outcomes = outcomes?;
+    if let Some(cache) = cache {
+        if let Err(err) = cache.save() {
+            tracing::warn!("{:#}", err);
+        }
+    }
+
+    let mut summary = Summary::default();
+    let mut tally = Tally::default();
+    for (challenge, num_stages, outcome) in outcomes {
+        let prefix = format!("day{:02} ", challenge.day());
+        tally += outcome.report(
+            &*challenge,
+            &prefix,
+            num_stages,
+            answers.as_ref(),
+            &mut summary,
+        );
+    }
+    summary.wall(start.elapsed());
+    if num_runs > 1 {
+        println!("\n{}", summary);
+    }
+    Ok(tally.status())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use structopt::StructOpt;
+
+    use super::{data_source_in, Cli, Command, RunOptions};
+    use crate::{
+        testing::{Fake, Plain, TempDir},
+        Registry, Source, Stage,
+    };
+
+    fn registry() -> Registry {
+        Registry::new()
+            .register(Fake::<7>)
+            .register(Plain)
+            .register(Fake::<2>)
+    }
+
+    fn run_options(args: &[&str]) -> anyhow::Result<RunOptions> {
+        let cli = Cli::from_iter_safe(["aoc", "run"].iter().chain(args))?;
+        match cli.command {
+            Command::Run(options) => Ok(options),
+            command => panic!("Parsed {:?} as another command", command),
+        }
+    }
+
+    /// Days and stages selected by `args`
+    fn select(args: &[&str]) -> anyhow::Result<Vec<(u8, Vec<Stage>)>> {
+        let selection = run_options(args)?.select(&registry())?;
+        Ok(selection
+            .into_iter()
+            .map(|(challenge, stages)| (challenge.day(), stages))
+            .collect())
+    }
+
+    #[test]
+    fn test_arguments() {
+        assert!(run_options(&[]).is_err());
+        assert!(run_options(&["--day", "1", "--all"]).is_err());
+        assert!(run_options(&["--all", "day01/data.txt"]).is_err());
+        assert!(run_options(&["--day", "1", "-s", "stage1,"]).is_err());
+
+        let options = run_options(&["-d", "3", "-"]).unwrap();
+        assert_eq!(Some(3), options.day);
+        assert_eq!(Some(Source::Stdin), options.filename);
+    }
+
+    #[test]
+    fn test_select_days() {
+        let both = vec![Stage::Stage1, Stage::Stage2];
+        assert_eq!(vec![(7, both.clone())], select(&["--day", "7"]).unwrap());
+        assert_eq!(
+            vec![2, 4, 7],
+            select(&["--all"])
+                .unwrap()
+                .into_iter()
+                .map(|(day, _)| day)
+                .collect::<Vec<_>>()
+        );
+        let err = select(&["--day", "5"]).unwrap_err();
+        assert_eq!("No challenge registered for day 5", err.to_string());
+    }
+
+    #[test]
+    fn test_select_stages() {
+        let naive = Stage::Custom("stage1-naive");
+        assert_eq!(
+            vec![(2, vec![Stage::Stage2])],
+            select(&["-d", "2", "-s", "2"]).unwrap()
+        );
+        assert_eq!(
+            vec![(2, vec![naive, Stage::Stage1])],
+            select(&["-d", "2", "-s", "stage1-naive,1"]).unwrap()
+        );
+        assert_eq!(
+            vec![(7, vec![Stage::Stage1, naive, Stage::Stage2])],
+            select(&["-d", "7", "--cross-check"]).unwrap()
+        );
+        assert_eq!(
+            vec![(4, vec![Stage::Stage1])],
+            select(&["-d", "4", "-s", "1", "--cross-check"]).unwrap()
+        );
+
+        // Every selected day must have the selected stages
+        let err = select(&["--all", "-s", "stage1-naive"]).unwrap_err();
+        assert_eq!("Selecting the stages of day 4", err.to_string());
+    }
+
+    #[test]
+    fn test_data_source() {
+        let dir = TempDir::new("runner");
+        std::fs::create_dir_all(dir.join("day07")).unwrap();
+        std::fs::write(dir.join("day07/data.txt"), "").unwrap();
+
+        let options = run_options(&["--all"]).unwrap();
+        assert_eq!(
+            Source::File(dir.join("day07/data.txt")),
+            data_source_in(&options, &dir, 7).unwrap()
+        );
+        let err = data_source_in(&options, &dir, 2).unwrap_err();
+        assert!(err.to_string().starts_with("No input found for day 2"));
+
+        // An explicit source is used for any day, found or not
+        let options = run_options(&["-d", "2", "other.txt"]).unwrap();
+        assert_eq!(
+            Source::File(PathBuf::from("other.txt")),
+            data_source_in(&options, &dir, 2).unwrap()
+        );
+        let options = run_options(&["-d", "7", "-"]).unwrap();
+        assert_eq!(Source::Stdin, data_source_in(&options, &dir, 7).unwrap());
+    }
+}
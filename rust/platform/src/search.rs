@@ -0,0 +1,283 @@
+//! Shortest path searches over graphs of states, such as positions on a map or configurations of
+//! a puzzle.
+//!
+//! The graph is never built: searches start from a state and ask `neighbours` for the states
+//! reachable from each state they visit, until one satisfies `goal`. States only need to be
+//! hashable, so they can be anything from a grid position to a whole game board.
+//!
+//! Every search runs in a `search` span and ends with a debug event telling how many states it
+//! visited and discovered, which shows how well a heuristic prunes the search with `-vv`.
+
+use std::{
+    cmp::Reverse,
+    collections::{hash_map, BinaryHeap, HashMap, VecDeque},
+    hash::Hash,
+    ops::Add,
+};
+
+/// Costs of the edges of a graph. They must not be negative, the default value being zero.
+pub trait Cost: Copy + Ord + Add<Output = Self> + Default {}
+
+impl<C: Copy + Ord + Add<Output = C> + Default> Cost for C {}
+
+/// Shortest path found by a search
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path<S, C> {
+    /// States from the start to the goal, both included
+    pub states: Vec<S>,
+    pub cost: C,
+}
+
+struct Node<S, C> {
+    state: S,
+    parent: Option<usize>,
+    cost: C,
+}
+
+/// States discovered by a search, along with the cheapest way found to reach them
+struct Explored<S, C> {
+    index: HashMap<S, usize>,
+    nodes: Vec<Node<S, C>>,
+}
+
+impl<S: Clone + Eq + Hash, C: Cost> Explored<S, C> {
+    fn new(start: S) -> Self {
+        Self {
+            index: HashMap::from([(start.clone(), 0)]),
+            nodes: vec![Node {
+                state: start,
+                parent: None,
+                cost: C::default(),
+            }],
+        }
+    }
+
+    /// Records that `state` can be reached at `cost` from the node `parent`, returning its node
+    /// unless it was already reached at a lower or equal cost
+    fn discover(&mut self, state: S, parent: usize, cost: C) -> Option<usize> {
+        match self.index.entry(state) {
+            hash_map::Entry::Occupied(entry) => {
+                let node = &mut self.nodes[*entry.get()];
+                if cost >= node.cost {
+                    return None;
+                }
+                node.parent = Some(parent);
+                node.cost = cost;
+                Some(*entry.get())
+            }
+            hash_map::Entry::Vacant(entry) => {
+                self.nodes.push(Node {
+                    state: entry.key().clone(),
+                    parent: Some(parent),
+                    cost,
+                });
+                Some(*entry.insert(self.nodes.len() - 1))
+            }
+        }
+    }
+
+    /// Path to the node `found`, tracing how much of the graph the search went through
+    fn finish(self, found: Option<usize>, visited: usize) -> Option<Path<S, C>> {
+        tracing::debug!(
+            visited,
+            discovered = self.nodes.len(),
+            found = found.is_some(),
+            "Search finished"
+        );
+        let goal = found?;
+        let mut states = Vec::new();
+        let mut next = Some(goal);
+        while let Some(i) = next {
+            states.push(self.nodes[i].state.clone());
+            next = self.nodes[i].parent;
+        }
+        states.reverse();
+        Some(Path {
+            states,
+            cost: self.nodes[goal].cost,
+        })
+    }
+}
+
+/// Path to the first state satisfying `goal` with the fewest steps, exploring the states in
+/// order of distance to `start`. Its cost is the number of steps.
+pub fn bfs<S, I>(
+    start: S,
+    mut neighbours: impl FnMut(&S) -> I,
+    mut goal: impl FnMut(&S) -> bool,
+) -> Option<Path<S, usize>>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    let _span = tracing::debug_span!("search", algorithm = "bfs").entered();
+    let mut explored = Explored::new(start);
+    let mut queue = VecDeque::from([0]);
+    let mut visited = 0;
+    let found = loop {
+        let Some(i) = queue.pop_front() else {
+            break None;
+        };
+        visited += 1;
+        let Node { state, cost, .. } = &explored.nodes[i];
+        let (state, cost) = (state.clone(), *cost);
+        if goal(&state) {
+            break Some(i);
+        }
+        for next in neighbours(&state) {
+            if let Some(j) = explored.discover(next, i, cost + 1) {
+                queue.push_back(j);
+            }
+        }
+    };
+    explored.finish(found, visited)
+}
+
+/// Cheapest path to a state satisfying `goal`, `neighbours` giving the states reachable from a
+/// state along with the cost of reaching them
+pub fn dijkstra<S, C, I>(
+    start: S,
+    neighbours: impl FnMut(&S) -> I,
+    goal: impl FnMut(&S) -> bool,
+) -> Option<Path<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let _span = tracing::debug_span!("search", algorithm = "dijkstra").entered();
+    best_first(start, neighbours, |_| C::default(), goal)
+}
+
+/// Same as [`dijkstra`], visiting first the states from which `heuristic` estimates the goal to
+/// be cheapest to reach. The path is the cheapest one as long as the heuristic never
+/// overestimates the cost to the goal, such as the Manhattan distance on a map.
+pub fn astar<S, C, I>(
+    start: S,
+    neighbours: impl FnMut(&S) -> I,
+    heuristic: impl FnMut(&S) -> C,
+    goal: impl FnMut(&S) -> bool,
+) -> Option<Path<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let _span = tracing::debug_span!("search", algorithm = "astar").entered();
+    best_first(start, neighbours, heuristic, goal)
+}
+
+/// Visits the states by increasing estimated cost of the path to the goal going through them
+fn best_first<S, C, I>(
+    start: S,
+    mut neighbours: impl FnMut(&S) -> I,
+    mut heuristic: impl FnMut(&S) -> C,
+    mut goal: impl FnMut(&S) -> bool,
+) -> Option<Path<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let mut heap = BinaryHeap::from([Reverse((heuristic(&start), C::default(), 0))]);
+    let mut explored = Explored::new(start);
+    let mut visited = 0;
+    let found = loop {
+        let Some(Reverse((_, cost, i))) = heap.pop() else {
+            break None;
+        };
+        // The state was reached more cheaply after being queued, and visited then
+        if cost > explored.nodes[i].cost {
+            continue;
+        }
+        visited += 1;
+        let state = explored.nodes[i].state.clone();
+        if goal(&state) {
+            break Some(i);
+        }
+        for (next, step) in neighbours(&state) {
+            if let Some(j) = explored.discover(next, i, cost + step) {
+                let estimate = heuristic(&explored.nodes[j].state);
+                heap.push(Reverse((cost + step + estimate, cost + step, j)));
+            }
+        }
+    };
+    explored.finish(found, visited)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{astar, bfs, dijkstra, Path};
+    use crate::grid::Grid;
+
+    const RISKS: &str = "1163751742\n1381373672\n2136511328\n3694931569\n7463417111\n\
+                         1319128137\n1359912421\n3125421639\n1293138521\n2311944581\n";
+
+    #[test]
+    fn test_bfs() {
+        let maze = Grid::<char>::parse_chars("S.#.\n#...\n..#E\n").unwrap();
+        let start = maze.positions(|&c| c == 'S').next().unwrap();
+        let path = bfs(
+            start,
+            |&pos| maze.neighbours4(pos).filter(|&next| maze[next] != '#'),
+            |&pos| maze[pos] == 'E',
+        )
+        .unwrap();
+        assert_eq!(5, path.cost);
+        assert_eq!(
+            vec![(0, 0), (0, 1), (1, 1), (1, 2), (1, 3), (2, 3)],
+            path.states
+        );
+
+        // Unreachable goals and starting on the goal
+        assert_eq!(None, bfs(0u8, |&n| n.checked_add(3), |&n| n == 10));
+        assert_eq!(
+            Some(Path {
+                states: vec![7],
+                cost: 0
+            }),
+            bfs(7, |&n| [n + 1], |&n| n == 7)
+        );
+    }
+
+    #[test]
+    fn test_weighted() {
+        let risks = Grid::parse_digits(RISKS).unwrap();
+        let end = (risks.nrows() - 1, risks.ncols() - 1);
+        let neighbours = |&pos: &(usize, usize)| {
+            risks
+                .neighbours4(pos)
+                .map(|next| (next, u32::from(risks[next])))
+                .collect::<Vec<_>>()
+        };
+
+        let path = dijkstra((0, 0), neighbours, |&pos| pos == end).unwrap();
+        assert_eq!(40, path.cost);
+        assert_eq!(Some(&(0, 0)), path.states.first());
+        assert_eq!(Some(&end), path.states.last());
+        let total: u32 = path.states[1..]
+            .iter()
+            .map(|&pos| u32::from(risks[pos]))
+            .sum();
+        assert_eq!(path.cost, total);
+
+        let manhattan = |&(row, col): &(usize, usize)| (end.0 - row + end.1 - col) as u32;
+        let path = astar((0, 0), neighbours, manhattan, |&pos| pos == end).unwrap();
+        assert_eq!(40, path.cost);
+    }
+
+    #[test]
+    fn test_cheaper_detour() {
+        // Going through `b` costs less than the direct edge to `c`, found after `c` was queued
+        let edges = |state: &char| match state {
+            'a' => vec![('c', 10), ('b', 1)],
+            'b' => vec![('c', 2)],
+            'c' => vec![('d', 1)],
+            _ => vec![],
+        };
+        let path = dijkstra('a', edges, |&s| s == 'd').unwrap();
+        assert_eq!(vec!['a', 'b', 'c', 'd'], path.states);
+        assert_eq!(4, path.cost);
+        assert_eq!(None, dijkstra('a', edges, |&s| s == 'e'));
+    }
+}
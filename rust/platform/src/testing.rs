@@ -0,0 +1,181 @@
+//! Helpers shared by the unit tests of the platform: temporary directories and fake challenges.
+
+use std::{
+    ops::Deref,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use crate::{Answer, Challenge, CustomStage, Stage};
+
+/// Directory of a single test, removed along with its contents when dropped
+#[derive(Debug)]
+pub(crate) struct TempDir(PathBuf);
+
+impl TempDir {
+    /// Creates an empty directory, named after `name` and unique even among tests using the same
+    /// name, which run concurrently in the same process
+    pub fn new(name: &str) -> Self {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+        let dir = std::env::temp_dir().join(format!(
+            "aoc-platform-{}-{}-{}",
+            std::process::id(),
+            COUNT.fetch_add(1, Ordering::Relaxed),
+            name
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        Self(dir)
+    }
+}
+
+impl Deref for TempDir {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+/// Sums whitespace-separated numbers, with a variant wrongly ignoring numbers above 50. The
+/// second stage is not implemented.
+#[derive(Debug)]
+pub(crate) struct Sum;
+
+impl Challenge for Sum {
+    const DAY: u8 = 1;
+    type Input = Vec<u64>;
+
+    const CUSTOM_STAGES: &'static [CustomStage] = &[CustomStage::variant(
+        Stage::Stage1,
+        "stage1-capped",
+        "Sums small numbers",
+    )];
+
+    fn parse(&self, data: &str) -> anyhow::Result<Self::Input> {
+        Ok(data
+            .split_whitespace()
+            .map(str::parse)
+            .collect::<Result<_, _>>()?)
+    }
+
+    fn stage1(&self, input: &Self::Input) -> anyhow::Result<Answer> {
+        Ok(input.iter().sum::<u64>().into())
+    }
+
+    fn stage2(&self, _input: &Self::Input) -> anyhow::Result<Answer> {
+        todo!()
+    }
+
+    fn custom(&self, _name: &str, input: &Self::Input) -> anyhow::Result<Answer> {
+        Ok(input.iter().filter(|&&n| n <= 50).sum::<u64>().into())
+    }
+}
+
+/// Measures its input: stage1 counts its bytes and stage2 answers it back, trimmed. The day is
+/// chosen by the test, so that several of them can be registered. Parsing and stage1 log what
+/// they do, through `log` and `tracing` respectively.
+#[derive(Debug, Clone)]
+pub(crate) struct Fake<const D: u8>;
+
+impl<const D: u8> Challenge for Fake<D> {
+    const DAY: u8 = D;
+    type Input = String;
+
+    const CUSTOM_STAGES: &'static [CustomStage] = &[
+        CustomStage::new("stats", "Counts the words of the input"),
+        CustomStage::variant(
+            Stage::Stage1,
+            "stage1-naive",
+            "Counts the characters one by one",
+        ),
+    ];
+
+    fn parse(&self, data: &str) -> anyhow::Result<Self::Input> {
+        log::debug!("Parsing {} bytes", data.len());
+        Ok(data.trim().to_owned())
+    }
+
+    fn stage1(&self, input: &Self::Input) -> anyhow::Result<Answer> {
+        tracing::debug!("Counting {} bytes", input.len());
+        Ok(input.len().into())
+    }
+
+    fn stage2(&self, input: &Self::Input) -> anyhow::Result<Answer> {
+        Ok(input.as_str().into())
+    }
+
+    fn custom(&self, name: &str, input: &Self::Input) -> anyhow::Result<Answer> {
+        match name {
+            "stats" => Ok(input.split_whitespace().count().into()),
+            "stage1-naive" => Ok(input.chars().count().into()),
+            _ => anyhow::bail!("unknown custom stage {}", name),
+        }
+    }
+}
+
+/// Challenge of day 4 with neither custom stages nor any implemented stage
+#[derive(Debug)]
+pub(crate) struct Plain;
+
+impl Challenge for Plain {
+    const DAY: u8 = 4;
+    type Input = ();
+
+    fn parse(&self, _data: &str) -> anyhow::Result<Self::Input> {
+        Ok(())
+    }
+
+    fn stage1(&self, _input: &Self::Input) -> anyhow::Result<Answer> {
+        todo!()
+    }
+
+    fn stage2(&self, _input: &Self::Input) -> anyhow::Result<Answer> {
+        todo!()
+    }
+}
+
+/// Challenge of day 6 whose first stage takes 50ms
+#[derive(Debug)]
+pub(crate) struct Slow;
+
+impl Challenge for Slow {
+    const DAY: u8 = 6;
+    type Input = ();
+
+    fn parse(&self, _data: &str) -> anyhow::Result<Self::Input> {
+        Ok(())
+    }
+
+    fn stage1(&self, _input: &Self::Input) -> anyhow::Result<Answer> {
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        Ok(Self::DAY.into())
+    }
+
+    fn stage2(&self, _input: &Self::Input) -> anyhow::Result<Answer> {
+        todo!()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TempDir;
+
+    #[test]
+    fn test_temp_dir() {
+        let dir = TempDir::new("temp");
+        let other = TempDir::new("temp");
+        assert_ne!(&*dir, &*other);
+
+        std::fs::write(dir.join("data.txt"), "1\n").unwrap();
+        let path = dir.to_path_buf();
+        drop(dir);
+        assert!(!path.exists());
+        assert!(other.is_dir());
+    }
+}
@@ -0,0 +1,157 @@
+//! Verification of stage answers against a manifest of expected answers.
+//!
+//! The manifest is a TOML file with one table per day, keyed by stage:
+//!
+//! ```toml
+//! [day01]
+//! stage1 = 1316
+//! stage2 = 1344
+//! ```
+
+use std::{collections::BTreeMap, fmt, path::Path, str::FromStr};
+
+use anyhow::Context;
+use serde::Deserialize;
+use structopt::StructOpt;
+
+use crate::{Answer, Stage};
+
+#[derive(Debug, StructOpt)]
+pub(crate) struct VerifyOptions {
+    /// Check answers against the answers manifest, failing on mismatch
+    #[structopt(long)]
+    pub verify: bool,
+    /// Answers manifest used by `--verify`
+    #[structopt(long, name = "answers file", default_value = "answers.toml")]
+    pub answers: std::path::PathBuf,
+}
+
+impl VerifyOptions {
+    /// Loads the answers manifest if verification was requested
+    pub fn load(&self) -> anyhow::Result<Option<Answers>> {
+        if self.verify {
+            Answers::load(&self.answers).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+/// Expected answers, indexed by day (or example name) then stage
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(transparent)]
+pub struct Answers {
+    days: BTreeMap<String, BTreeMap<String, Answer>>,
+}
+
+impl FromStr for Answers {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(toml::from_str(s)?)
+    }
+}
+
+impl Answers {
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        std::fs::read_to_string(path)
+            .context("Cannot read answers file")
+            .and_then(|s| s.parse())
+            .with_context(|| format!("Loading answers from {}", path.display()))
+    }
+
+    pub fn expected(&self, day: u8, stage: Stage) -> Option<&Answer> {
+        self.get(&format!("day{:02}", day), stage)
+    }
+
+    /// Expected answer of a stage, for the table `name` of the manifest
+    pub fn get(&self, name: &str, stage: Stage) -> Option<&Answer> {
+        self.days.get(name)?.get(&stage.to_string())
+    }
+
+    pub fn check(&self, day: u8, stage: Stage, answer: &Answer) -> Verdict {
+        match self.expected(day, stage) {
+            None => Verdict::Missing,
+            Some(expected) if expected == answer => Verdict::Pass,
+            Some(expected) => Verdict::Fail {
+                expected: expected.clone(),
+            },
+        }
+    }
+}
+
+/// Outcome of checking an answer against the manifest
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    Fail { expected: Answer },
+    Missing,
+}
+
+impl Verdict {
+    pub fn is_failure(&self) -> bool {
+        matches!(self, Self::Fail { .. })
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Pass => write!(f, "pass"),
+            Self::Fail { expected } => write!(f, "FAIL, expected {}", expected),
+            Self::Missing => write!(f, "missing"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Answers, Verdict};
+    use crate::{Answer, Stage};
+
+    const MANIFEST: &str = r#"
+[day01]
+stage1 = 1316
+stage2 = "ABCD"
+
+[day04]
+stage2 = """
+#..#
+.##.
+"""
+"#;
+
+    #[test]
+    fn test_parsing() {
+        let answers: Answers = MANIFEST.parse().unwrap();
+
+        assert_eq!(
+            Some(&Answer::Integer(1316)),
+            answers.expected(1, Stage::Stage1)
+        );
+        assert_eq!(
+            Some(&Answer::from("ABCD")),
+            answers.expected(1, Stage::Stage2)
+        );
+        assert_eq!(
+            Some(&Answer::from("#..#\n.##.")),
+            answers.expected(4, Stage::Stage2)
+        );
+        assert_eq!(None, answers.expected(4, Stage::Stage1));
+    }
+
+    #[test]
+    fn test_check() {
+        let answers: Answers = MANIFEST.parse().unwrap();
+
+        assert_eq!(Verdict::Pass, answers.check(1, Stage::Stage1, &1316.into()));
+        assert_eq!(
+            Verdict::Fail {
+                expected: Answer::Integer(1316)
+            },
+            answers.check(1, Stage::Stage1, &1317.into())
+        );
+        assert_eq!(Verdict::Missing, answers.check(2, Stage::Stage1, &0.into()));
+    }
+}